@@ -0,0 +1,14 @@
+fn main() {
+    // The C header is generated from src/ffi.rs into OUT_DIR, a test in src/ffi.rs checks that the committed
+    // include/risk.h matches it
+    #[cfg(feature = "ffi")]
+    {
+        println!("cargo:rerun-if-changed=src/ffi.rs");
+        println!("cargo:rerun-if-changed=cbindgen.toml");
+        let dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let out_dir = std::env::var("OUT_DIR").unwrap();
+        let config = cbindgen::Config::from_file(format!("{}/cbindgen.toml", dir)).unwrap();
+        cbindgen::Builder::new().with_config(config).with_src(format!("{}/src/ffi.rs", dir)).generate()
+            .expect("Could not generate the C header").write_to_file(format!("{}/risk.h", out_dir));
+    }
+}
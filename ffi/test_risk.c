@@ -0,0 +1,142 @@
+#include <stdio.h>
+#include <string.h>
+
+#include "risk.h"
+
+static int failures = 0;
+
+#define CHECK(condition) do { \
+    if (!(condition)) { \
+        fprintf(stderr, "%s:%d: %s\n", __FILE__, __LINE__, #condition); \
+        failures++; \
+    } \
+} while (0)
+
+static int same_territories(const RiskGameState *a, const RiskGameState *b) {
+    for (uint8_t t = 0; t < RISK_TERRITORIES; t++) {
+        uint8_t player_a, armies_a, player_b, armies_b;
+        risk_state_territory(a, t, &player_a, &armies_a);
+        risk_state_territory(b, t, &player_b, &armies_b);
+        if (player_a != player_b || armies_a != armies_b) {
+            return 0;
+        }
+    }
+    return 1;
+}
+
+static void test_names_and_moves(void) {
+    CHECK(risk_api_version() == RISK_API_VERSION);
+    CHECK(strcmp(risk_territory_name(0), "Alaska") == 0);
+    CHECK(risk_territory_name(RISK_TERRITORIES) == NULL);
+
+    RiskMove attack = { RISK_MOVE_ATTACK, 0, 1, 3 };
+    RiskMove decoded;
+    CHECK(risk_move_decode(risk_move_encode(attack), &decoded) == RISK_STATUS_OK);
+    CHECK(memcmp(&attack, &decoded, sizeof attack) == 0);
+    CHECK(risk_move_decode(0x07000000, &decoded) == RISK_STATUS_INVALID_ARGUMENT);
+    CHECK(risk_move_decode(0, NULL) == RISK_STATUS_NULL_POINTER);
+}
+
+static void test_states(void) {
+    RiskGameState *state = risk_state_new(42);
+    RiskGameState *same = risk_state_new(42);
+    CHECK(same_territories(state, same));
+
+    uint8_t reinforcements = 0;
+    CHECK(risk_state_phase(state, &reinforcements) == RISK_PHASE_REINFORCE && reinforcements > 0);
+    CHECK(risk_state_current_player(state) == 0);
+    CHECK(!risk_state_is_finished(state));
+
+    size_t count = risk_state_legal_moves(state, NULL, 0);
+    RiskMove moves[1024];
+    CHECK(count > 0 && count <= 1024);
+    CHECK(risk_state_legal_moves(state, moves, 1024) == count);
+    CHECK(moves[0].kind == RISK_MOVE_REINFORCE);
+
+    RiskGameState *after = NULL;
+    CHECK(risk_state_apply_move(state, moves[0], 0, &after) == RISK_STATUS_OK);
+    uint8_t player, armies_before, armies_after;
+    risk_state_territory(state, moves[0].from, &player, &armies_before);
+    risk_state_territory(after, moves[0].from, &player, &armies_after);
+    CHECK(armies_after == armies_before + moves[0].armies);
+
+    RiskMove pass = { RISK_MOVE_PASS, 0, 0, 0 };
+    RiskGameState *unused = NULL;
+    CHECK(risk_state_apply_move(state, pass, 0, &unused) == RISK_STATUS_ILLEGAL_MOVE && unused == NULL);
+
+    risk_state_free(after);
+    risk_state_free(same);
+    risk_state_free(state);
+    risk_state_free(NULL);
+}
+
+static void test_seeded_dice(void) {
+    uint8_t players[RISK_TERRITORIES], armies[RISK_TERRITORIES];
+    for (uint8_t t = 0; t < RISK_TERRITORIES; t++) {
+        players[t] = t % 2;
+        armies[t] = 3;
+    }
+    RiskGameState *state = risk_state_from_parts(0, RISK_PHASE_ATTACK, 0, players, armies);
+    CHECK(state != NULL);
+    players[0] = 2;
+    CHECK(risk_state_from_parts(0, RISK_PHASE_ATTACK, 0, players, armies) == NULL);
+
+    /* Alaska (0) attacks Northwest Territory (1) */
+    RiskMove attack = { RISK_MOVE_ATTACK, 0, 1, 2 };
+    int differs = 0;
+    for (uint64_t seed = 0; seed < 20; seed++) {
+        RiskGameState *a = NULL, *b = NULL, *c = NULL;
+        risk_state_apply_move(state, attack, seed, &a);
+        risk_state_apply_move(state, attack, seed, &b);
+        risk_state_apply_move(state, attack, seed + 1, &c);
+        CHECK(same_territories(a, b));
+        differs |= !same_territories(a, c);
+        risk_state_free(a);
+        risk_state_free(b);
+        risk_state_free(c);
+    }
+    CHECK(differs);
+    risk_state_free(state);
+}
+
+static void test_bots(void) {
+    CHECK(risk_bot_new("no such bot") == NULL);
+    RiskBot *bots[2] = { risk_bot_new("strategic"), risk_bot_new("random") };
+    CHECK(bots[0] != NULL && bots[1] != NULL);
+
+    /* Bots may attack with more armies than the legal moves list, so only applying their moves is checked */
+    RiskGameState *state = risk_state_new(7);
+    int played = 0;
+    while (!risk_state_is_finished(state) && played < 5000) {
+        RiskMove move;
+        uint8_t player = risk_state_current_player(state);
+        CHECK(risk_bot_make_move(bots[player], state, &move) == RISK_STATUS_OK);
+
+        RiskGameState *next = NULL;
+        if (risk_state_apply_move(state, move, (uint64_t)played, &next) != RISK_STATUS_OK) {
+            failures++;
+            break;
+        }
+        risk_state_free(state);
+        state = next;
+        played++;
+    }
+    CHECK(played > 0);
+
+    risk_state_free(state);
+    risk_bot_free(bots[0]);
+    risk_bot_free(bots[1]);
+}
+
+int main(void) {
+    test_names_and_moves();
+    test_states();
+    test_seeded_dice();
+    test_bots();
+    if (failures > 0) {
+        fprintf(stderr, "%d checks failed\n", failures);
+        return 1;
+    }
+    printf("All checks passed\n");
+    return 0;
+}
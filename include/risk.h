@@ -0,0 +1,112 @@
+/*
+ * C API of the risk engine. Link against librisk (cargo build --release --features ffi).
+ *
+ * Game states and bots are opaque handles owned by the caller, free them with risk_state_free and risk_bot_free.
+ * Territories are indices below RISK_TERRITORIES, risk_territory_name gives their names. Players are 0 and 1.
+ * Moves are RiskMove structs with one of RISK_MOVE_*, risk_move_encode packs them into 32 bits.
+ * risk_state_apply_move takes the seed for the dice, the same seed gives the same outcome.
+ */
+
+#ifndef RISK_H
+#define RISK_H
+
+/* Generated from src/ffi.rs by cbindgen when building with `--features ffi`, do not edit */
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#define RISK_API_VERSION 1
+
+#define RISK_TERRITORIES 42
+
+#define RISK_PHASE_REINFORCE 0
+
+#define RISK_PHASE_ATTACK 1
+
+#define RISK_PHASE_FORTIFY 2
+
+#define RISK_MOVE_PASS 0
+
+#define RISK_MOVE_REINFORCE 1
+
+#define RISK_MOVE_FORTIFY 2
+
+#define RISK_MOVE_ATTACK 3
+
+typedef enum RiskStatus {
+  RISK_STATUS_OK = 0,
+  RISK_STATUS_NULL_POINTER = -1,
+  RISK_STATUS_INVALID_ARGUMENT = -2,
+  RISK_STATUS_ILLEGAL_MOVE = -3,
+  RISK_STATUS_BOT_FAILED = -4,
+} RiskStatus;
+
+typedef struct RiskBot RiskBot;
+
+typedef struct RiskGameState RiskGameState;
+
+typedef struct RiskMove {
+  uint8_t kind;
+  uint8_t from;
+  uint8_t to;
+  uint8_t armies;
+} RiskMove;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+uint32_t risk_api_version(void);
+
+const char *risk_territory_name(uint8_t territory);
+
+uint32_t risk_move_encode(struct RiskMove m);
+
+enum RiskStatus risk_move_decode(uint32_t code, struct RiskMove *out);
+
+struct RiskGameState *risk_state_new(uint64_t seed);
+
+struct RiskGameState *risk_state_from_parts(uint8_t current_player,
+                                            uint8_t phase,
+                                            uint8_t reinforcements,
+                                            const uint8_t *players,
+                                            const uint8_t *armies);
+
+struct RiskGameState *risk_state_clone(const struct RiskGameState *state);
+
+void risk_state_free(struct RiskGameState *state);
+
+uint8_t risk_state_current_player(const struct RiskGameState *state);
+
+uint8_t risk_state_phase(const struct RiskGameState *state, uint8_t *reinforcements);
+
+enum RiskStatus risk_state_territory(const struct RiskGameState *state,
+                                     uint8_t territory,
+                                     uint8_t *player,
+                                     uint8_t *armies);
+
+bool risk_state_is_finished(const struct RiskGameState *state);
+
+size_t risk_state_legal_moves(const struct RiskGameState *state,
+                              struct RiskMove *out,
+                              size_t capacity);
+
+enum RiskStatus risk_state_apply_move(const struct RiskGameState *state,
+                                      struct RiskMove m,
+                                      uint64_t seed,
+                                      struct RiskGameState **out);
+
+struct RiskBot *risk_bot_new(const char *spec);
+
+void risk_bot_free(struct RiskBot *bot);
+
+enum RiskStatus risk_bot_make_move(struct RiskBot *bot,
+                                   const struct RiskGameState *state,
+                                   struct RiskMove *out);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* RISK_H */
@@ -0,0 +1,56 @@
+import numpy as np
+import pytest
+
+import risk
+
+
+def test_state_and_moves():
+    state = risk.GameState(seed=1)
+    assert state.territories == risk.GameState(seed=1).territories
+    assert len(state.territories) == len(risk.TERRITORIES)
+    assert state.phase == "reinforce"
+
+    move = state.legal_moves()[0]
+    assert risk.Move.from_json(move.to_json()) == move
+    [(after, probability)] = state.apply_move(move)
+    assert probability == 1.0 and after.current_player == state.current_player
+
+    features = state.features()
+    assert features.dtype == np.float32 and features.shape == (risk.Env.OBSERVATION,)
+
+
+def test_attack_outcomes():
+    state = risk.GameState.from_parts(0, "attack", [(0, 5)] + [(1, 2)] * (len(risk.TERRITORIES) - 1))
+    attack = next(m for m in state.legal_moves() if '"attack"' in m.to_json())
+    outcomes = state.apply_move(attack)
+    assert len(outcomes) > 1
+    assert sum(p for _, p in outcomes) == pytest.approx(1.0)
+    with pytest.raises(ValueError):
+        risk.GameState.from_parts(0, "attack", [(0, 5)])
+
+
+def test_env_episode():
+    env = risk.Env(opponent="strategic", max_rounds=20, adjudicator="territories", territories=0.1)
+    observation = env.reset(seed=7)
+    assert observation.shape == (risk.Env.OBSERVATION,)
+    rng = np.random.default_rng(7)
+    done = False
+    while not done:
+        mask = env.action_mask()
+        assert mask.shape == (risk.Env.ACTIONS,)
+        observation, reward, done, info = env.step(int(rng.choice(np.flatnonzero(mask))))
+    assert info["winner"] in (0, 1, None)
+    with pytest.raises(ValueError):
+        env.step(0)
+
+
+def test_bots_and_arena(tmp_path):
+    assert risk.Bot("strategic").make_move(risk.GameState(seed=2)) in risk.GameState(seed=2).legal_moves()
+    wins_a, wins_b, draws = risk.play_games(4, "strategic", "random", adjudicator="territories")
+    assert wins_a + wins_b + draws == 4
+
+    path = str(tmp_path / "weights.bin")
+    risk.save_neural_weights(np.zeros(risk.NEURAL_WEIGHTS, dtype=np.float32), path)
+    risk.Bot("neural:" + path)
+    with pytest.raises(ValueError):
+        risk.Bot("mcts")
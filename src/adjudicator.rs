@@ -0,0 +1,196 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use strum::IntoEnumIterator;
+
+use crate::bots::random_bot::RandomBot;
+use crate::game::{Game, GameResult, PlayOptions};
+use crate::game_state::GameState;
+use crate::player::Player;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScoreWeights {
+    pub territories: f64,
+    pub armies: f64,
+    pub income: f64,
+    pub continents: f64,
+}
+
+impl Default for ScoreWeights {
+    fn default() -> Self {
+        Self { territories: 1.0, armies: 0.5, income: 2.0, continents: 3.0 }
+    }
+}
+
+// Decides the result of games that hit the round limit. Finished games are always won by the player left on the board.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Adjudicator {
+    #[default]
+    Draw,
+    Territories,
+    Armies,
+    Income,
+    Weighted(ScoreWeights),
+    // Plays the game to the end with two random bots, falling back to territories if that does not finish either
+    Playout,
+}
+
+impl Adjudicator {
+    pub const NAMES: &'static [&'static str] = &["draw", "territories", "armies", "income", "weighted", "playout"];
+
+    pub fn adjudicate(&self, game_state: GameState) -> GameResult {
+        if game_state.is_finished() {
+            return GameResult::Win(game_state.current_player());
+        }
+
+        match self {
+            Adjudicator::Draw => GameResult::Draw,
+            Adjudicator::Territories => Self::compare(|player| game_state.territories_states_of_player(player).len() as f64),
+            Adjudicator::Armies => Self::compare(|player| Self::armies(&game_state, player)),
+            Adjudicator::Income => Self::compare(|player| game_state.number_of_reinforcements(player) as f64),
+            Adjudicator::Weighted(weights) => Self::compare(|player| {
+                weights.territories * game_state.territories_states_of_player(player).len() as f64
+                    + weights.armies * Self::armies(&game_state, player)
+                    + weights.income * game_state.number_of_reinforcements(player) as f64
+                    + weights.continents * game_state.continents_for_player(player).len() as f64
+            }),
+            Adjudicator::Playout => {
+                let mut game = Game::from_state(RandomBot {}, RandomBot {}, game_state);
+                match game.play_until_end(&|state| Adjudicator::Territories.adjudicate(state), &PlayOptions::default()) {
+                    Ok((_, result)) => result,
+                    Err(_) => Adjudicator::Territories.adjudicate(game_state),
+                }
+            }
+        }
+    }
+
+    fn armies(game_state: &GameState, player: Player) -> f64 {
+        game_state.territories_states_of_player(player).iter().map(|t| t.state().armies() as f64).sum()
+    }
+
+    fn compare<F: Fn(Player) -> f64>(score: F) -> GameResult {
+        let scores = Player::iter().map(|player| (player, score(player))).collect::<Vec<_>>();
+        let (a, b) = (scores[0].1, scores[1].1);
+        if a > b {
+            GameResult::Win(scores[0].0)
+        } else if b > a {
+            GameResult::Win(scores[1].0)
+        } else {
+            GameResult::Draw
+        }
+    }
+}
+
+impl fmt::Display for Adjudicator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Adjudicator::Draw => write!(f, "draw"),
+            Adjudicator::Territories => write!(f, "territories"),
+            Adjudicator::Armies => write!(f, "armies"),
+            Adjudicator::Income => write!(f, "income"),
+            Adjudicator::Weighted(w) => write!(f, "weighted:territories={},armies={},income={},continents={}", w.territories, w.armies, w.income, w.continents),
+            Adjudicator::Playout => write!(f, "playout"),
+        }
+    }
+}
+
+// Accepts the names in `Adjudicator::NAMES`, weights can be overridden like `weighted:armies=1,income=0`
+impl FromStr for Adjudicator {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, parameters) = match s.split_once(':') {
+            Some((name, parameters)) => (name, Some(parameters)),
+            None => (s, None),
+        };
+
+        let adjudicator = match name.trim().to_lowercase().as_str() {
+            "draw" => Adjudicator::Draw,
+            "territories" | "territory" => Adjudicator::Territories,
+            "armies" | "army" => Adjudicator::Armies,
+            "income" | "reinforcements" => Adjudicator::Income,
+            "playout" | "random" => Adjudicator::Playout,
+            "weighted" => {
+                let mut weights = ScoreWeights::default();
+                for parameter in parameters.unwrap_or("").split(',').filter(|p| !p.trim().is_empty()) {
+                    let (key, value) = parameter.split_once('=').ok_or_else(|| format!("Expected key=value, found '{}'", parameter))?;
+                    let value = value.trim().parse::<f64>().map_err(|e| format!("Invalid weight '{}': {}", value, e))?;
+                    match key.trim() {
+                        "territories" => weights.territories = value,
+                        "armies" => weights.armies = value,
+                        "income" => weights.income = value,
+                        "continents" => weights.continents = value,
+                        key => return Err(format!("Unknown weight '{}'", key)),
+                    }
+                }
+                return Ok(Adjudicator::Weighted(weights));
+            }
+            _ => return Err(format!("Unknown adjudicator '{}', expected one of {}", name, Self::NAMES.join(", "))),
+        };
+
+        match parameters {
+            Some(_) => Err(format!("Adjudicator '{}' takes no parameters", name)),
+            None => Ok(adjudicator),
+        }
+    }
+}
+
+impl Serialize for Adjudicator {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Adjudicator {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use strum::EnumCount;
+
+    use crate::game::GameResult;
+    use crate::game_state::{GamePhase, GameState, GameStateDuringInitialPlacement, TerritoryState};
+    use crate::player::Player;
+    use crate::territories::Territory;
+
+    use super::{Adjudicator, ScoreWeights};
+
+    #[test]
+    fn parse_names() {
+        for name in Adjudicator::NAMES {
+            let adjudicator = name.parse::<Adjudicator>().unwrap();
+            assert!(adjudicator.to_string().starts_with(name));
+        }
+        assert_eq!("weighted:armies=1,income=0".parse::<Adjudicator>().unwrap(), Adjudicator::Weighted(ScoreWeights { armies: 1.0, income: 0.0, ..Default::default() }));
+        let weighted = Adjudicator::Weighted(ScoreWeights::default());
+        assert_eq!(weighted.to_string().parse::<Adjudicator>().unwrap(), weighted);
+        assert!("territories:1".parse::<Adjudicator>().is_err());
+        assert!("weighted:luck=1".parse::<Adjudicator>().is_err());
+        assert!("coin".parse::<Adjudicator>().is_err());
+    }
+
+    #[test]
+    fn initial_placement_is_even() {
+        // Both players start with the same number of territories and armies
+        let state = GameStateDuringInitialPlacement::new().place_random().start();
+        assert_eq!(Adjudicator::Draw.adjudicate(state), GameResult::Draw);
+        assert_eq!(Adjudicator::Territories.adjudicate(state), GameResult::Draw);
+        assert_eq!(Adjudicator::Armies.adjudicate(state), GameResult::Draw);
+    }
+
+    #[test]
+    fn playout_decides() {
+        // The other player is left with a single army, random play can not turn that around
+        for (player, current) in [(Player::A, Player::A), (Player::A, Player::B), (Player::B, Player::A)] {
+            let mut territories = [TerritoryState::new(player, 20); Territory::COUNT];
+            territories[Territory::Japan as usize] = TerritoryState::new(player.next(), 1);
+            let state = GameState::from_parts(current, GamePhase::Attack, territories);
+            assert_eq!(Adjudicator::Playout.adjudicate(state), GameResult::Win(player));
+        }
+    }
+}
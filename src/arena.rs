@@ -1,30 +1,103 @@
+use std::collections::HashMap;
 use std::sync::mpsc::channel;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::fmt::{self};
+use std::panic::{self, AssertUnwindSafe};
+use std::time::{Duration, Instant};
 
 use num_cpus;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
 use threadpool::ThreadPool;
 
-use crate::game::{Game, GameResult, PlayOptions};
-use crate::game_state::GameState;
+use crate::game::{self, Game, GameConfig, GameError, GameResult, PlayOptions};
+use crate::game_stats::{AggregateStats, GameStats};
+use crate::game_state::{GameState, GameStateDuringInitialPlacement};
 use crate::player::Player;
 use crate::bots::Bot;
+use crate::stats::{self, EloEstimate, Sprt, SprtStatus, Z_95};
+
+#[derive(Debug)]
+pub enum GameOutcome {
+    Finished { rounds: u16, result: GameResult, stats: GameStats },
+    // The player named in the error loses the game
+    Forfeit(GameError),
+    // The engine itself failed, the game is not scored
+    Error(String),
+}
+
+impl GameOutcome {
+    fn from_game(result: GameJobResult) -> Self {
+        match result {
+            Ok((rounds, result, stats)) => GameOutcome::Finished { rounds, result, stats },
+            Err(err) => GameOutcome::Forfeit(err),
+        }
+    }
+
+    fn result(&self) -> Option<GameResult> {
+        match self {
+            GameOutcome::Finished { result, .. } => Some(*result),
+            GameOutcome::Forfeit(err) => Some(GameResult::Win(err.player().next())),
+            GameOutcome::Error(_) => None,
+        }
+    }
+}
+
+type GameJobResult = Result<(u16, GameResult, GameStats), GameError>;
+
+fn play_game<BotA: Bot, BotB: Bot, Evaluator>(mut game: Game<BotA, BotB>, evaluate_result: &Evaluator) -> GameJobResult
+where
+Evaluator: Fn(GameState) -> GameResult {
+    let (rounds, result) = game.play_until_end(evaluate_result, &PlayOptions::default())?;
+    Ok((rounds, result, game.stats().clone()))
+}
 
 pub struct ArenaResult {
-    results: Vec<GameResult>,
-    rounds_played: Vec<u16>,
+    outcomes: Vec<GameOutcome>,
 }
 
 impl ArenaResult {
-    fn new(results: Vec<GameResult>, rounds_played: Vec<u16>) -> Self {
-        Self { results, rounds_played }
+    fn new(outcomes: Vec<GameOutcome>) -> Self {
+        Self { outcomes }
     }
 
+    pub fn outcomes(&self) -> &[GameOutcome] {
+        &self.outcomes
+    }
+
+    // Scored games, including forfeits but not engine errors
+    pub fn games(&self) -> u32 {
+        self.outcomes.iter().filter(|o| o.result().is_some()).count() as u32
+    }
+
+    // Forfeits of the opponent count as wins
     pub fn wins(&self, player: Player) -> u32 {
-        self.results.iter().filter(|r| r == &&GameResult::Win(player)).count() as u32
+        self.outcomes.iter().filter(|o| o.result() == Some(GameResult::Win(player))).count() as u32
     }
 
     pub fn draws(&self) -> u32 {
-        self.results.iter().filter(|r| r == &&GameResult::Draw).count() as u32
+        self.outcomes.iter().filter(|o| o.result() == Some(GameResult::Draw)).count() as u32
+    }
+
+    pub fn forfeits(&self, player: Player) -> u32 {
+        self.outcomes.iter().filter(|o| matches!(o, GameOutcome::Forfeit(err) if err.player() == player)).count() as u32
+    }
+
+    pub fn errors(&self) -> u32 {
+        self.outcomes.iter().filter(|o| matches!(o, GameOutcome::Error(_))).count() as u32
+    }
+
+    // Statistics of all games that were played to the end
+    pub fn game_stats(&self) -> impl Iterator<Item = &GameStats> {
+        self.outcomes.iter().filter_map(|o| match o {
+            GameOutcome::Finished { stats, .. } => Some(stats),
+            _ => None,
+        })
+    }
+
+    pub fn aggregate_stats(&self) -> AggregateStats {
+        AggregateStats::from_games(self.game_stats())
     }
 
     pub fn winner(&self) -> Option<Player> {
@@ -37,45 +110,564 @@ impl ArenaResult {
         }
     }
 
+    // 0 if no game was played to the end
     pub fn avg_rounds(&self) -> f64 {
-        self.rounds_played.iter().sum::<u16>() as f64 / self.rounds_played.len() as f64
+        let rounds_played = self.outcomes.iter().filter_map(|o| match o {
+            GameOutcome::Finished { rounds, .. } => Some(*rounds as f64),
+            _ => None,
+        }).collect::<Vec<_>>();
+        stats::mean(&rounds_played)
+    }
+
+    // Average thinking time per move in games that were played to the end
+    pub fn avg_move_time(&self, player: Player) -> Duration {
+        let (time, moves) = self.game_stats().map(|g| &g.players[player as usize]).fold((0, 0), |(time, moves), s| {
+            (time + s.think_time_us, moves + s.moves() as u64)
+        });
+        Duration::from_micros(time.checked_div(moves).unwrap_or(0))
+    }
+
+    pub fn win_rate_interval(&self, player: Player) -> (f64, f64) {
+        stats::wilson_interval(self.wins(player) as f64, self.games() as f64, Z_95)
+    }
+
+    pub fn elo_difference(&self, player: Player) -> EloEstimate {
+        stats::elo_estimate(self.wins(player), self.draws(), self.wins(player.next()), Z_95)
+    }
+
+    pub fn likelihood_of_superiority(&self, player: Player) -> f64 {
+        stats::likelihood_of_superiority(self.wins(player), self.wins(player.next()))
     }
 }
 
 impl fmt::Debug for ArenaResult {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Wins (A): {:?}, Wins (B): {:?}, Draws: {:?} (avg {} rounds)", self.wins(Player::A), self.wins(Player::B), self.draws(), self.avg_rounds())
+        let elo = self.elo_difference(Player::A);
+        write!(f, "Wins (A): {:?}, Wins (B): {:?}, Draws: {:?}, Forfeits (A): {}, Forfeits (B): {}, Errors: {} (avg {} rounds), Elo (A): {:+.1} [{:+.1}, {:+.1}], LOS (A): {:.1}%, Move time (A/B): {:?}/{:?}", self.wins(Player::A), self.wins(Player::B), self.draws(), self.forfeits(Player::A), self.forfeits(Player::B), self.errors(), self.avg_rounds(), elo.elo, elo.low, elo.high, self.likelihood_of_superiority(Player::A) * 100.0, self.avg_move_time(Player::A), self.avg_move_time(Player::B))
     }
 }
 
-pub fn play_games_with_default_bot_init<BotA: Bot + Default, BotB: Bot + Default, Evaluator>(games: u32, evaluate_result: &'static Evaluator) -> Result<ArenaResult, &'static str>
+// Results of seat-swapped games: in `normal` the first bot sits in seat A, in `swapped` it sits in seat B
+pub struct PairedArenaResult {
+    normal: ArenaResult,
+    swapped: ArenaResult,
+}
+
+impl PairedArenaResult {
+    pub fn normal(&self) -> &ArenaResult {
+        &self.normal
+    }
+
+    pub fn swapped(&self) -> &ArenaResult {
+        &self.swapped
+    }
+
+    pub fn games(&self) -> u32 {
+        self.normal.games() + self.swapped.games()
+    }
+
+    pub fn wins_first_bot(&self) -> u32 {
+        self.normal.wins(Player::A) + self.swapped.wins(Player::B)
+    }
+
+    pub fn wins_second_bot(&self) -> u32 {
+        self.normal.wins(Player::B) + self.swapped.wins(Player::A)
+    }
+
+    pub fn draws(&self) -> u32 {
+        self.normal.draws() + self.swapped.draws()
+    }
+
+    pub fn wins_seat(&self, player: Player) -> u32 {
+        self.normal.wins(player) + self.swapped.wins(player)
+    }
+
+    pub fn elo_difference(&self) -> EloEstimate {
+        stats::elo_estimate(self.wins_first_bot(), self.draws(), self.wins_second_bot(), Z_95)
+    }
+
+    pub fn likelihood_of_superiority(&self) -> f64 {
+        stats::likelihood_of_superiority(self.wins_first_bot(), self.wins_second_bot())
+    }
+}
+
+impl fmt::Debug for PairedArenaResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "First bot in seat A: {:?}", self.normal)?;
+        writeln!(f, "First bot in seat B: {:?}", self.swapped)?;
+        writeln!(f, "Seats: Wins (A): {}, Wins (B): {}", self.wins_seat(Player::A), self.wins_seat(Player::B))?;
+        let elo = self.elo_difference();
+        write!(f, "Combined: Wins (first): {}, Wins (second): {}, Draws: {}, Elo (first): {:+.1} [{:+.1}, {:+.1}], LOS (first): {:.1}%", self.wins_first_bot(), self.wins_second_bot(), self.draws(), elo.elo, elo.low, elo.high, self.likelihood_of_superiority() * 100.0)
+    }
+}
+
+pub struct SprtResult {
+    games: PairedArenaResult,
+    status: SprtStatus,
+    llr: f64,
+}
+
+impl SprtResult {
+    pub fn games(&self) -> &PairedArenaResult {
+        &self.games
+    }
+
+    pub fn status(&self) -> SprtStatus {
+        self.status
+    }
+
+    pub fn llr(&self) -> f64 {
+        self.llr
+    }
+}
+
+impl fmt::Debug for SprtResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:?}", self.games)?;
+        write!(f, "SPRT: {:?} (LLR {:.2})", self.status, self.llr)
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct CancelHandle(Arc<AtomicBool>);
+
+impl CancelHandle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ArenaProgress {
+    pub finished: u32,
+    pub total: u32,
+    pub wins_a: u32,
+    pub wins_b: u32,
+    pub draws: u32,
+    pub errors: u32,
+    pub elapsed: Duration,
+}
+
+impl ArenaProgress {
+    fn new(total: u32, started: Instant) -> Self {
+        Self { finished: 0, total, wins_a: 0, wins_b: 0, draws: 0, errors: 0, elapsed: started.elapsed() }
+    }
+
+    fn record(&mut self, outcome: &GameOutcome, started: Instant) {
+        self.finished += 1;
+        self.elapsed = started.elapsed();
+        match outcome.result() {
+            Some(GameResult::Win(Player::A)) => self.wins_a += 1,
+            Some(GameResult::Win(Player::B)) => self.wins_b += 1,
+            Some(GameResult::Draw) => self.draws += 1,
+            None => self.errors += 1,
+        }
+    }
+
+    pub fn eta(&self) -> Option<Duration> {
+        if self.finished == 0 {
+            return None;
+        }
+        Some(self.elapsed.mul_f64((self.total - self.finished) as f64 / self.finished as f64))
+    }
+}
+
+impl fmt::Display for ArenaProgress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{} games, W/D/L (A): {}/{}/{}", self.finished, self.total, self.wins_a, self.draws, self.wins_b)?;
+        if let Some(eta) = self.eta() {
+            write!(f, ", ETA {}s", eta.as_secs())?;
+        }
+        Ok(())
+    }
+}
+
+type ProgressCallback = Arc<dyn Fn(&ArenaProgress) + Send + Sync>;
+
+// Once cancelled, games that have not started yet are skipped and the result only contains finished games
+#[derive(Clone, Default)]
+pub struct ArenaOptions {
+    progress: Option<ProgressCallback>,
+    cancel: Option<CancelHandle>,
+    config: GameConfig,
+}
+
+impl ArenaOptions {
+    pub fn game_config(mut self, config: GameConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn on_progress<F>(mut self, callback: F) -> Self
+    where
+    F: Fn(&ArenaProgress) + Send + Sync + 'static {
+        self.progress = Some(Arc::new(callback));
+        self
+    }
+
+    pub fn cancel_handle(mut self, handle: CancelHandle) -> Self {
+        self.cancel = Some(handle);
+        self
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.as_ref().is_some_and(|c| c.is_cancelled())
+    }
+
+    fn report(&self, progress: &ArenaProgress) {
+        if let Some(callback) = &self.progress {
+            callback(progress);
+        }
+    }
+}
+
+pub fn play_games_with_default_bot_init<BotA, BotB, Evaluator>(games: u32, evaluate_result: Evaluator) -> Result<ArenaResult, &'static str>
+where
+BotA: Bot + Default + 'static,
+BotB: Bot + Default + 'static,
+Evaluator: Fn(GameState) -> GameResult + Clone + Send + Sync + 'static {
+    play_games(games, evaluate_result, || BotA::default(), || BotB::default())
+}
+
+pub fn play_games<BotA, BotB, F, G, Evaluator>(games: u32, evaluate_result: Evaluator, bot_a_factory: F, bot_b_factory: G) -> Result<ArenaResult, &'static str>
 where
 BotA: Bot + 'static,
 BotB: Bot + 'static,
-Evaluator: Fn(GameState) -> GameResult + Send + Sync + 'static {
-    play_games(games, evaluate_result, || BotA::default(), || BotB::default())
+F: Fn() -> BotA,
+G: Fn() -> BotB,
+Evaluator: Fn(GameState) -> GameResult + Clone + Send + Sync + 'static {
+    play_games_with_options(games, evaluate_result, bot_a_factory, bot_b_factory, &ArenaOptions::default())
 }
 
-pub fn play_games<BotA: Bot, BotB: Bot, F, G, Evaluator>(games: u32, evaluate_result: &'static Evaluator, bot_a_factory: F, bot_b_factory: G) -> Result<ArenaResult, &'static str>
+pub fn play_games_with_options<BotA, BotB, F, G, Evaluator>(games: u32, evaluate_result: Evaluator, bot_a_factory: F, bot_b_factory: G, options: &ArenaOptions) -> Result<ArenaResult, &'static str>
 where
 BotA: Bot + 'static,
 BotB: Bot + 'static,
 F: Fn() -> BotA,
 G: Fn() -> BotB,
-Evaluator: Fn(GameState) -> GameResult + Send + Sync + 'static {
+Evaluator: Fn(GameState) -> GameResult + Clone + Send + Sync + 'static {
+    let jobs = (0..games).map(|_| {
+        let bot_a = bot_a_factory();
+        let bot_b = bot_b_factory();
+        let evaluate_result = evaluate_result.clone();
+        let config = options.config;
+        move || play_game(Game::new(bot_a, bot_b).with_config(config), &evaluate_result)
+    }).collect::<Vec<_>>();
+
+    Ok(ArenaResult::new(run_games(jobs, options).into_iter().map(|(_, outcome)| outcome).collect()))
+}
+
+// Plays every random starting layout twice, the second time with the bots in swapped seats, so that
+// the advantage of moving first cancels out. Both games of a pair also share the seed of the dice.
+pub fn play_paired_games<BotA, BotB, F, G, Evaluator>(pairs: u32, evaluate_result: Evaluator, bot_a_factory: F, bot_b_factory: G) -> Result<PairedArenaResult, &'static str>
+where
+BotA: Bot + 'static,
+BotB: Bot + 'static,
+F: Fn() -> BotA,
+G: Fn() -> BotB,
+Evaluator: Fn(GameState) -> GameResult + Clone + Send + Sync + 'static {
+    play_paired_games_with_options(pairs, evaluate_result, bot_a_factory, bot_b_factory, &ArenaOptions::default())
+}
+
+pub fn play_paired_games_with_options<BotA, BotB, F, G, Evaluator>(pairs: u32, evaluate_result: Evaluator, bot_a_factory: F, bot_b_factory: G, options: &ArenaOptions) -> Result<PairedArenaResult, &'static str>
+where
+BotA: Bot + 'static,
+BotB: Bot + 'static,
+F: Fn() -> BotA,
+G: Fn() -> BotB,
+Evaluator: Fn(GameState) -> GameResult + Clone + Send + Sync + 'static {
+    let mut jobs: Vec<Box<dyn FnOnce() -> GameJobResult + Send>> = Vec::with_capacity(pairs as usize * 2);
+    for _ in 0..pairs {
+        let seed = rand::random();
+        let start = GameStateDuringInitialPlacement::new().place_random_with(&mut StdRng::seed_from_u64(seed)).start();
+
+        let config = options.config;
+
+        let (bot_a, bot_b) = (bot_a_factory(), bot_b_factory());
+        let evaluate = evaluate_result.clone();
+        jobs.push(Box::new(move || play_game(Game::from_state(bot_a, bot_b, start).with_config(config).with_seed(seed), &evaluate)));
+
+        let (bot_a, bot_b) = (bot_a_factory(), bot_b_factory());
+        let evaluate = evaluate_result.clone();
+        jobs.push(Box::new(move || play_game(Game::from_state(bot_b, bot_a, start).with_config(config).with_seed(seed), &evaluate)));
+    }
+
+    let (mut normal, mut swapped) = (vec![], vec![]);
+    for (idx, outcome) in run_games(jobs, options) {
+        if idx % 2 == 0 { normal.push(outcome) } else { swapped.push(outcome) }
+    }
+    Ok(PairedArenaResult { normal: ArenaResult::new(normal), swapped: ArenaResult::new(swapped) })
+}
+
+// Plays seat-swapped pairs of games until the SPRT accepts one of its hypotheses about the first bot, or until
+// `max_games`, rounded down to whole pairs, were played. Games still running when the test ends are discarded,
+// queued ones are not started.
+pub fn play_sprt<BotA, BotB, F, G, Evaluator>(sprt: Sprt, max_games: u32, evaluate_result: Evaluator, bot_a_factory: F, bot_b_factory: G) -> Result<SprtResult, &'static str>
+where
+BotA: Bot + 'static,
+BotB: Bot + 'static,
+F: Fn() -> BotA,
+G: Fn() -> BotB,
+Evaluator: Fn(GameState) -> GameResult + Clone + Send + Sync + 'static {
+    play_sprt_with_options(sprt, max_games, evaluate_result, bot_a_factory, bot_b_factory, &ArenaOptions::default())
+}
+
+pub fn play_sprt_with_options<BotA, BotB, F, G, Evaluator>(sprt: Sprt, max_games: u32, evaluate_result: Evaluator, bot_a_factory: F, bot_b_factory: G, options: &ArenaOptions) -> Result<SprtResult, &'static str>
+where
+BotA: Bot + 'static,
+BotB: Bot + 'static,
+F: Fn() -> BotA,
+G: Fn() -> BotB,
+Evaluator: Fn(GameState) -> GameResult + Clone + Send + Sync + 'static {
+    let started = Instant::now();
+    let threads = (num_cpus::get() as f64 * 1.2) as usize;
+    let pool = ThreadPool::new(threads);
+    let (tx, rx) = channel();
+    // Set once the test is decided, queued games are then skipped
+    let stop = CancelHandle::new();
+
+    let submit = |pair: u32| {
+        let seed = rand::random();
+        let start = GameStateDuringInitialPlacement::new().place_random_with(&mut StdRng::seed_from_u64(seed)).start();
+        for swapped in [false, true] {
+            let (tx, stop, options) = (tx.clone(), stop.clone(), options.clone());
+            let (bot_a, bot_b) = (bot_a_factory(), bot_b_factory());
+            let evaluate_result = evaluate_result.clone();
+            let config = options.config;
+            pool.execute(move|| {
+                if stop.is_cancelled() || options.is_cancelled() {
+                    return;
+                }
+                let outcome = play_isolated(move || if swapped {
+                    play_game(Game::from_state(bot_b, bot_a, start).with_config(config).with_seed(seed), &evaluate_result)
+                } else {
+                    play_game(Game::from_state(bot_a, bot_b, start).with_config(config).with_seed(seed), &evaluate_result)
+                });
+                tx.send((pair, swapped, outcome)).ok();
+            });
+        }
+    };
+
+    let max_pairs = max_games / 2;
+    let mut submitted = 0;
+    while submitted < std::cmp::min(threads.div_ceil(2) as u32, max_pairs) {
+        submit(submitted);
+        submitted += 1;
+    }
+
+    let mut games = PairedArenaResult { normal: ArenaResult::new(vec![]), swapped: ArenaResult::new(vec![]) };
+    let mut progress = ArenaProgress::new(max_pairs * 2, started);
+    let mut status = SprtStatus::Continue;
+    let mut llr = 0.0;
+    let mut halves: HashMap<u32, GameOutcome> = HashMap::new();
+    let mut pairs = 0;
+    while pairs < max_pairs && !options.is_cancelled() {
+        // Workers only stop sending once `stop` is set or the arena is cancelled
+        let Ok((pair, swapped, outcome)) = rx.recv_timeout(Duration::from_millis(100)) else { continue };
+        progress.record(&outcome, started);
+        options.report(&progress);
+
+        // Only complete pairs are scored, so both bots played both seats of every layout
+        let Some(other) = halves.remove(&pair) else {
+            halves.insert(pair, outcome);
+            continue;
+        };
+        let (normal, swapped) = if swapped { (other, outcome) } else { (outcome, other) };
+        games.normal.outcomes.push(normal);
+        games.swapped.outcomes.push(swapped);
+        pairs += 1;
+
+        llr = sprt.llr(games.wins_first_bot(), games.draws(), games.wins_second_bot());
+        status = sprt.status(games.wins_first_bot(), games.draws(), games.wins_second_bot());
+        if status != SprtStatus::Continue {
+            break;
+        }
+        if submitted < max_pairs {
+            submit(submitted);
+            submitted += 1;
+        }
+    }
+    stop.cancel();
+
+    Ok(SprtResult { games, status, llr })
+}
+
+// Panics of the bots are already turned into forfeits by `Game`, anything else that panics is an engine error
+fn play_isolated<J>(job: J) -> GameOutcome
+where
+J: FnOnce() -> GameJobResult {
+    match panic::catch_unwind(AssertUnwindSafe(job)) {
+        Ok(result) => GameOutcome::from_game(result),
+        Err(payload) => GameOutcome::Error(game::panic_message(payload.as_ref())),
+    }
+}
+
+// Runs the games on a thread pool and returns the outcomes of all games that were not cancelled,
+// together with the index of their job and in the order of the jobs
+fn run_games<J>(jobs: Vec<J>, options: &ArenaOptions) -> Vec<(usize, GameOutcome)>
+where
+J: FnOnce() -> GameJobResult + Send + 'static {
+    let started = Instant::now();
     let pool = ThreadPool::new((num_cpus::get() as f64 * 1.2) as usize);
+    let games = jobs.len();
 
     let (tx, rx) = channel();
-    for _ in 0..games {
+    for (idx, job) in jobs.into_iter().enumerate() {
         let tx = tx.clone();
-        let bot_a = bot_a_factory();
-        let bot_b = bot_b_factory();
+        let options = options.clone();
         pool.execute(move|| {
-            let mut game = Game::new(bot_a, bot_b);
-            tx.send(game.play_until_end(evaluate_result, &PlayOptions::default()).unwrap()).expect("channel will be there waiting for the pool");
+            let outcome = if options.is_cancelled() { None } else { Some(play_isolated(job)) };
+            tx.send((idx, outcome)).expect("channel will be there waiting for the pool");
         });
     }
+    drop(tx);
+
+    let mut progress = ArenaProgress::new(games as u32, started);
+    let mut results = vec![];
+    for (idx, outcome) in rx.iter().take(games) {
+        if let Some(outcome) = outcome {
+            progress.record(&outcome, started);
+            options.report(&progress);
+            results.push((idx, outcome));
+        }
+    }
+    results.sort_by_key(|(idx, _)| *idx);
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::time::Duration;
+
+    use crate::bots::random_bot::RandomBot;
+    use crate::bots::{Bot, BotError};
+    use crate::game::{self, GameConfig, GameError, TimeControl, TimeoutPenalty};
+    use crate::game_state::{GameState, Move};
+    use crate::player::Player;
+    use crate::stats::{Sprt, SprtStatus};
+    use crate::territories::Territory;
+
+    use super::{play_games, play_games_with_options, play_isolated, play_paired_games, play_sprt, ArenaOptions, CancelHandle, GameOutcome};
+
+    #[test]
+    fn paired_games_play_both_seats() {
+        let result = play_paired_games(3, game::evaluate_win, || RandomBot {}, || RandomBot {}).unwrap();
+        assert_eq!(result.normal().games(), 3);
+        assert_eq!(result.swapped().games(), 3);
+        assert_eq!(result.wins_first_bot() + result.wins_second_bot() + result.draws(), 6);
+        assert_eq!(result.wins_seat(Player::A) + result.wins_seat(Player::B), result.wins_first_bot() + result.wins_second_bot());
+
+        // Both games of a pair roll the same dice, different pairs do not
+        let normal = result.normal().game_stats().map(|s| s.seed.unwrap()).collect::<Vec<_>>();
+        let swapped = result.swapped().game_stats().map(|s| s.seed.unwrap()).collect::<Vec<_>>();
+        assert_eq!(normal, swapped);
+        assert!(normal[0] != normal[1] && normal[1] != normal[2]);
+    }
 
-    let results = rx.iter().take(games as usize).collect::<Vec<_>>();
-    Ok(ArenaResult::new(results.iter().map(|r| r.1).collect(), results.iter().map(|r| r.0).collect()))
+    #[test]
+    fn sprt_stops_at_max_games() {
+        let result = play_sprt(Sprt::new(-1000.0, 1000.0), 4, game::evaluate_win, || RandomBot {}, || RandomBot {}).unwrap();
+        assert!(result.games().games() <= 4);
+        assert_eq!(result.games().normal().games(), result.games().swapped().games());
+    }
+
+    struct PanickingBot;
+
+    impl Bot for PanickingBot {
+        fn make_move(&mut self, _game_state: GameState) -> Move {
+            panic!("broken bot");
+        }
+    }
+
+    struct IllegalBot;
+
+    impl Bot for IllegalBot {
+        fn make_move(&mut self, _game_state: GameState) -> Move {
+            Move::Attack { from: Territory::Alaska, to: Territory::Argentina, attacking: 1 }
+        }
+    }
+
+    #[test]
+    fn sprt_stops_early() {
+        let result = play_sprt(Sprt::new(0.0, 50.0), 1000, game::evaluate_win, || RandomBot {}, || IllegalBot).unwrap();
+        assert_eq!(result.status(), SprtStatus::AcceptH1);
+        assert!(result.games().games() < 100);
+        assert_eq!(result.games().normal().games(), result.games().swapped().games());
+    }
+
+    #[test]
+    fn panics_and_illegal_moves_are_forfeits() {
+        let result = play_games(4, game::evaluate_win, || PanickingBot {}, || IllegalBot {}).unwrap();
+        assert_eq!(result.games(), 4);
+        assert_eq!(result.forfeits(Player::A), 4);
+        assert_eq!(result.wins(Player::B), 4);
+        assert_eq!(result.avg_rounds(), 0.0);
+
+        let result = play_games(4, game::evaluate_win, || RandomBot {}, || IllegalBot {}).unwrap();
+        assert_eq!(result.forfeits(Player::B), 4);
+        for outcome in result.outcomes() {
+            assert!(matches!(outcome, GameOutcome::Forfeit(GameError::IllegalMove { player: Player::B, .. })));
+        }
+    }
+
+    struct SlowBot;
+
+    impl Bot for SlowBot {
+        fn make_move(&mut self, game_state: GameState) -> Move {
+            std::thread::sleep(Duration::from_millis(5));
+            RandomBot {}.make_move(game_state)
+        }
+    }
+
+    #[test]
+    fn time_control_penalties() {
+        let time_control = TimeControl { move_ms: Some(1), ..TimeControl::default() };
+        let options = ArenaOptions::default().game_config(GameConfig { max_rounds: 3, time_control, ..GameConfig::default() });
+        let result = play_games_with_options(2, game::evaluate_win, || SlowBot {}, || RandomBot {}, &options).unwrap();
+        assert_eq!(result.forfeits(Player::A), 2);
+        assert!(matches!(result.outcomes()[0], GameOutcome::Forfeit(GameError::BotFailed { err: BotError::Timeout(_), .. })));
+
+        let time_control = TimeControl { penalty: TimeoutPenalty::RandomMove, ..time_control };
+        let options = ArenaOptions::default().game_config(GameConfig { max_rounds: 3, time_control, ..GameConfig::default() });
+        let result = play_games_with_options(2, game::evaluate_win, || SlowBot {}, || RandomBot {}, &options).unwrap();
+        assert_eq!(result.forfeits(Player::A), 0);
+        assert!(result.avg_move_time(Player::A) >= Duration::from_millis(5));
+        assert!(result.game_stats().all(|stats| stats.players[0].timeouts == stats.players[0].moves() && stats.players[1].timeouts == 0));
+    }
+
+    #[test]
+    fn engine_errors_are_not_scored() {
+        let outcome = play_isolated(|| panic!("engine failure"));
+        assert!(matches!(outcome, GameOutcome::Error(ref message) if message == "engine failure"));
+    }
+
+    #[test]
+    fn cancelled_arena_returns_partial_result() {
+        let cancel = CancelHandle::new();
+        let finished = Arc::new(AtomicU32::new(0));
+        let options = {
+            let cancel = cancel.clone();
+            let finished = finished.clone();
+            ArenaOptions::default().cancel_handle(cancel.clone()).on_progress(move |progress| {
+                finished.store(progress.finished, Ordering::SeqCst);
+                if progress.finished >= 2 {
+                    cancel.cancel();
+                }
+            })
+        };
+        let result = play_games_with_options(200, game::evaluate_win, || RandomBot {}, || RandomBot {}, &options).unwrap();
+        assert!(cancel.is_cancelled());
+        assert!(result.games() >= 2 && result.games() < 200);
+        assert_eq!(finished.load(Ordering::SeqCst), result.games());
+    }
 }
@@ -0,0 +1,202 @@
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use risk::adjudicator::Adjudicator;
+use risk::game::{GameConfig, TimeControl};
+
+// Settings of all subcommands, read from a TOML file. Every value can be overridden on the command line.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub game: GameSection,
+    pub train: TrainConfig,
+    pub play: PlayConfig,
+    pub arena: ArenaConfig,
+    pub tournament: TournamentConfig,
+    pub server: ServerConfig,
+}
+
+impl Config {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let content = fs::read_to_string(&path).map_err(|e| format!("Could not read {}: {}", path.as_ref().display(), e))?;
+        toml::from_str(&content).map_err(|e| format!("Invalid config {}: {}", path.as_ref().display(), e))
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GameSection {
+    pub max_rounds: u16,
+    pub max_moves_per_turn: usize,
+    pub adjudicator: Adjudicator,
+    pub time_control: TimeControl,
+}
+
+impl Default for GameSection {
+    fn default() -> Self {
+        let config = GameConfig::default();
+        Self { max_rounds: config.max_rounds, max_moves_per_turn: config.max_moves_per_turn, adjudicator: Adjudicator::Draw, time_control: config.time_control }
+    }
+}
+
+impl GameSection {
+    pub fn game_config(&self) -> GameConfig {
+        GameConfig { max_rounds: self.max_rounds, max_moves_per_turn: self.max_moves_per_turn, time_control: self.time_control }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TrainConfig {
+    // neural or rule_based
+    pub bot: String,
+    pub generations: usize,
+    pub islands: usize,
+    pub migration_interval: usize,
+    pub migrants: usize,
+    pub evaluation_games: u32,
+    // Training games are short, so they are decided by this instead of the adjudicator in [game]
+    pub evaluation_adjudicator: Adjudicator,
+    pub benchmark_games: u32,
+    pub metrics: String,
+    // Best genome of every island is written here after each generation
+    pub weights_dir: Option<String>,
+}
+
+impl Default for TrainConfig {
+    fn default() -> Self {
+        Self {
+            bot: if cfg!(feature = "neural") { "neural" } else { "rule_based" }.to_string(),
+            generations: 4,
+            islands: 4,
+            migration_interval: 2,
+            migrants: 2,
+            evaluation_games: 20,
+            evaluation_adjudicator: Adjudicator::Territories,
+            benchmark_games: 100,
+            metrics: "training_metrics".to_string(),
+            weights_dir: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PlayConfig {
+    pub bot_a: String,
+    pub bot_b: String,
+    pub record: Option<String>,
+    pub images: Option<String>,
+    pub verbose: bool,
+    // Full screen terminal UI instead of the plain output, images and verbose are ignored then
+    pub tui: bool,
+    pub delay_ms: u64,
+}
+
+impl Default for PlayConfig {
+    fn default() -> Self {
+        Self { bot_a: "rule_based".to_string(), bot_b: "random".to_string(), record: None, images: None, verbose: false, tui: false, delay_ms: 300 }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ArenaConfig {
+    pub bot_a: String,
+    pub bot_b: String,
+    pub games: u32,
+    // Plays every starting position twice with swapped seats
+    pub paired: bool,
+    pub stats: Option<String>,
+    pub game_stats: Option<String>,
+}
+
+impl Default for ArenaConfig {
+    fn default() -> Self {
+        Self { bot_a: "rule_based".to_string(), bot_b: "random".to_string(), games: 100, paired: false, stats: None, game_stats: None }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TournamentConfig {
+    pub bots: Vec<String>,
+    pub games_per_pairing: u32,
+    // Round robin if not set
+    pub swiss_rounds: Option<usize>,
+}
+
+impl Default for TournamentConfig {
+    fn default() -> Self {
+        // Builds without the neural feature can't create neural bots
+        let third = if cfg!(feature = "neural") { "neural" } else { "strategic" };
+        Self { bots: vec!["random".to_string(), "rule_based".to_string(), third.to_string()], games_per_pairing: 20, swiss_rounds: None }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    pub address: String,
+    // Runs until stopped if not set
+    pub games: Option<u32>,
+    // Per move, a client that does not answer in time forfeits
+    pub timeout_ms: u64,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self { address: "127.0.0.1:7777".to_string(), games: None, timeout_ms: 30000 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risk::adjudicator::Adjudicator;
+    use risk::bots::BotRegistry;
+    use risk::game::TimeoutPenalty;
+
+    use super::Config;
+
+    #[test]
+    fn parse_partial_config() {
+        let config: Config = toml::from_str(r#"
+            [game]
+            max_rounds = 50
+            adjudicator = "armies"
+
+            [game.time_control]
+            move_ms = 100
+            penalty = "random_move"
+
+            [arena]
+            bot_b = "strategic"
+            paired = true
+        "#).unwrap();
+        assert_eq!(config.game.max_rounds, 50);
+        assert_eq!(config.game.max_moves_per_turn, 200);
+        assert_eq!(config.game.adjudicator, Adjudicator::Armies);
+        assert_eq!(config.game.time_control.move_ms, Some(100));
+        assert_eq!(config.game.time_control.penalty, TimeoutPenalty::RandomMove);
+        assert_eq!(config.arena.bot_a, "rule_based");
+        assert_eq!(config.arena.bot_b, "strategic");
+        assert!(config.arena.paired);
+        assert_eq!(config.train.generations, 4);
+
+        assert!(toml::from_str::<Config>("[arena]\ngame = 3").is_err());
+        assert!(toml::from_str::<Config>("[game]\nadjudicator = \"coin\"").is_err());
+    }
+
+    #[test]
+    fn default_bots_exist() {
+        let config = Config::default();
+        let registry = BotRegistry::default();
+        let mut bots = vec![&config.train.bot, &config.play.bot_a, &config.play.bot_b, &config.arena.bot_a, &config.arena.bot_b];
+        bots.extend(&config.tournament.bots);
+        for bot in bots {
+            assert!(registry.factory(bot).is_ok(), "{}", bot);
+        }
+    }
+}
@@ -0,0 +1,220 @@
+use std::time::Duration;
+
+use clap::Args;
+
+use risk::arena;
+use risk::bots::registry::BotRegistry;
+use risk::bots::Bot;
+use risk::game::{Game, GameResult, PlayOptions};
+use risk::game_stats::{self, AggregateStats, GameStats};
+use risk::player::Player;
+use risk::tournament::{Pairing, Tournament};
+use crate::tui::{self, runner::{self, Console}};
+
+use super::config::{ArenaConfig, Config, PlayConfig};
+
+#[derive(Args)]
+pub struct PlayArgs {
+    #[arg(long)]
+    bot_a: Option<String>,
+    #[arg(long)]
+    bot_b: Option<String>,
+    #[arg(long, help = "Save the game to this JSON file for replay, render and analyze")]
+    record: Option<String>,
+    #[arg(long, help = "Draw the map after every round into this folder")]
+    images: Option<String>,
+    #[arg(short, long)]
+    verbose: bool,
+    #[arg(long, help = "Watch or play the game in a full screen terminal UI")]
+    tui: bool,
+    #[arg(long, help = "Pause between moves in the terminal UI in milliseconds")]
+    delay_ms: Option<u64>,
+}
+
+#[derive(Args)]
+pub struct ArenaArgs {
+    #[arg(long)]
+    bot_a: Option<String>,
+    #[arg(long)]
+    bot_b: Option<String>,
+    #[arg(short, long)]
+    games: Option<u32>,
+    #[arg(long, help = "Play every starting position twice with swapped seats")]
+    paired: bool,
+    #[arg(long, help = "Write the aggregated statistics to this JSON file")]
+    stats: Option<String>,
+    #[arg(long, help = "Write the statistics of every game to this JSONL file")]
+    game_stats: Option<String>,
+}
+
+#[derive(Args)]
+pub struct TournamentArgs {
+    #[arg(help = "Bots to enter, replaces the list from the config")]
+    bots: Vec<String>,
+    #[arg(short, long)]
+    games_per_pairing: Option<u32>,
+    #[arg(long)]
+    swiss_rounds: Option<usize>,
+}
+
+pub fn play(args: PlayArgs, config: Config) -> Result<(), String> {
+    let mut play = config.play.clone();
+    if let Some(bot_a) = args.bot_a {
+        play.bot_a = bot_a;
+    }
+    if let Some(bot_b) = args.bot_b {
+        play.bot_b = bot_b;
+    }
+    if args.record.is_some() {
+        play.record = args.record;
+    }
+    if args.images.is_some() {
+        play.images = args.images;
+    }
+    play.verbose |= args.verbose;
+    play.tui |= args.tui;
+    if let Some(delay_ms) = args.delay_ms {
+        play.delay_ms = delay_ms;
+    }
+
+    let mut console = None;
+    let bot_a = make_bot(&play, &play.bot_a, &mut console)?;
+    let bot_b = make_bot(&play, &play.bot_b, &mut console)?;
+    let mut game = Game::new(bot_a, bot_b).with_config(config.game.game_config());
+    if play.record.is_some() {
+        game = game.record_moves(&play.bot_a, &play.bot_b);
+    }
+    let adjudicator = config.game.adjudicator;
+
+    if play.tui {
+        let (game, outcome) = tui::run(game, [play.bot_a.clone(), play.bot_b.clone()], move |state| adjudicator.adjudicate(state), Duration::from_millis(play.delay_ms), console)
+            .map_err(|e| format!("Terminal UI failed: {}", e))?;
+        match outcome {
+            Some(Ok(result)) => print_result(&play, game.round(), result),
+            Some(Err(err)) => println!("{}", err),
+            None => println!("Stopped after {} rounds", game.round()),
+        }
+        return save_record(&play, &game);
+    }
+
+    let mut options = PlayOptions::default();
+    if let Some(folder) = &play.images {
+        #[cfg(feature = "render")]
+        {
+            options = options.save_map_images(folder);
+        }
+        #[cfg(not(feature = "render"))]
+        return Err(format!("Can not save map images to {}, built without the render feature", folder));
+    }
+    if play.verbose {
+        options = options.verbose();
+    }
+
+    let (rounds, result) = game.play_until_end(&|state| adjudicator.adjudicate(state), &options).map_err(|e| e.to_string())?;
+    print_result(&play, rounds, result);
+    save_record(&play, &game)
+}
+
+// In the terminal UI a human player types into the UI instead of stdin
+fn make_bot(play: &PlayConfig, name: &str, console: &mut Option<Console>) -> Result<Box<dyn Bot>, String> {
+    if !play.tui || !name.starts_with("human") {
+        return BotRegistry::default().create(name);
+    }
+    if console.is_some() {
+        return Err("The terminal UI supports only one human player".to_string());
+    }
+    let (bot, channels) = runner::channel_human();
+    *console = Some(channels);
+    Ok(Box::new(bot))
+}
+
+fn print_result(play: &PlayConfig, rounds: u16, result: GameResult) {
+    match result {
+        GameResult::Win(player) => println!("{} ({}) won after {} rounds", player, if player == Player::A { &play.bot_a } else { &play.bot_b }, rounds),
+        GameResult::Draw => println!("Draw after {} rounds", rounds),
+    }
+}
+
+fn save_record(play: &PlayConfig, game: &Game<Box<dyn Bot>, Box<dyn Bot>>) -> Result<(), String> {
+    if let (Some(path), Some(record)) = (&play.record, game.record()) {
+        record.save(path).map_err(|e| format!("Could not save {}: {}", path, e))?;
+    }
+    Ok(())
+}
+
+pub fn arena(args: ArenaArgs, config: Config) -> Result<(), String> {
+    let mut arena = config.arena.clone();
+    if let Some(bot_a) = args.bot_a {
+        arena.bot_a = bot_a;
+    }
+    if let Some(bot_b) = args.bot_b {
+        arena.bot_b = bot_b;
+    }
+    if let Some(games) = args.games {
+        arena.games = games;
+    }
+    arena.paired |= args.paired;
+    if args.stats.is_some() {
+        arena.stats = args.stats;
+    }
+    if args.game_stats.is_some() {
+        arena.game_stats = args.game_stats;
+    }
+
+    let registry = BotRegistry::default();
+    let factory_a = registry.factory(&arena.bot_a)?;
+    let factory_b = registry.factory(&arena.bot_b)?;
+    let cancel = super::cancel_on_ctrl_c();
+    let options = super::arena_options(&config, &cancel);
+    let adjudicator = config.game.adjudicator;
+    let evaluate = move |state| adjudicator.adjudicate(state);
+
+    if arena.paired {
+        let results = arena::play_paired_games_with_options(arena.games.div_ceil(2), evaluate, &factory_a, &factory_b, &options)?;
+        println!("\r{} vs {}: {:?}", arena.bot_a, arena.bot_b, results);
+        write_stats(&arena, results.normal().game_stats().chain(results.swapped().game_stats()).collect())
+    } else {
+        let results = arena::play_games_with_options(arena.games, evaluate, &factory_a, &factory_b, &options)?;
+        println!("\r{} vs {}: {:?}", arena.bot_a, arena.bot_b, results);
+        write_stats(&arena, results.game_stats().collect())
+    }
+}
+
+fn write_stats(arena: &ArenaConfig, stats: Vec<&GameStats>) -> Result<(), String> {
+    if let Some(path) = &arena.stats {
+        AggregateStats::from_games(stats.iter().copied()).write_json(path).map_err(|e| format!("Could not write {}: {}", path, e))?;
+    }
+    if let Some(path) = &arena.game_stats {
+        game_stats::write_jsonl(path, stats.iter().copied()).map_err(|e| format!("Could not write {}: {}", path, e))?;
+    }
+    Ok(())
+}
+
+pub fn tournament(args: TournamentArgs, config: Config) -> Result<(), String> {
+    let mut tournament = config.tournament.clone();
+    if !args.bots.is_empty() {
+        tournament.bots = args.bots;
+    }
+    if let Some(games_per_pairing) = args.games_per_pairing {
+        tournament.games_per_pairing = games_per_pairing;
+    }
+    if args.swiss_rounds.is_some() {
+        tournament.swiss_rounds = args.swiss_rounds;
+    }
+
+    let pairing = match tournament.swiss_rounds {
+        Some(rounds) => Pairing::Swiss { rounds },
+        None => Pairing::RoundRobin,
+    };
+    let cancel = super::cancel_on_ctrl_c();
+    let mut runner = Tournament::new(pairing, tournament.games_per_pairing).arena_options(super::arena_options(&config, &cancel));
+    let registry = BotRegistry::default();
+    for name in &tournament.bots {
+        runner = runner.add_bot(name, registry.factory(name)?);
+    }
+
+    let adjudicator = config.game.adjudicator;
+    let result = runner.play(move |state| adjudicator.adjudicate(state))?;
+    println!("\r{}", result);
+    Ok(())
+}
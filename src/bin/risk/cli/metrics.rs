@@ -0,0 +1,192 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use risk::arena::ArenaResult;
+use risk::evolver::{Evaluator, Evolver, Mutator};
+use risk::player::Player;
+use risk::stats::{self, Z_95};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchmarkRecord {
+    pub opponent: String,
+    pub games: u32,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+    pub win_rate: f64,
+    pub win_rate_low: f64,
+    pub win_rate_high: f64,
+}
+
+impl BenchmarkRecord {
+    pub fn from_arena_result(opponent: &str, result: &ArenaResult, player: Player) -> Self {
+        let wins = result.wins(player);
+        let games = result.games();
+        let (win_rate_low, win_rate_high) = stats::wilson_interval(wins as f64, games as f64, Z_95);
+        BenchmarkRecord {
+            opponent: opponent.to_string(),
+            games,
+            wins,
+            draws: result.draws(),
+            losses: result.wins(player.next()),
+            win_rate: if games == 0 { 0.0 } else { wins as f64 / games as f64 },
+            win_rate_low,
+            win_rate_high,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GenerationRecord {
+    pub generation: usize,
+    pub island: usize,
+    pub fitness: Vec<usize>,
+    pub best_fitness: usize,
+    pub mean_fitness: f64,
+    pub median_fitness: f64,
+    pub diversity: f64,
+    pub mutation: String,
+    pub benchmarks: Vec<BenchmarkRecord>,
+    // The islands evolve in parallel, so this is the time of the whole generation and the same for every island
+    pub generation_time_secs: f64,
+}
+
+impl GenerationRecord {
+    pub fn from_evolver<E, M, const LENGTH: usize, const POPULATION: usize>(generation: usize, island: usize, evolver: &Evolver<E, M, LENGTH, POPULATION>, generation_time: Duration) -> Self
+    where E: Evaluator<LENGTH>, M: Mutator {
+        let fitness = evolver.fitnesses();
+        let values = fitness.iter().map(|&f| f as f64).collect::<Vec<_>>();
+        GenerationRecord {
+            generation,
+            island,
+            best_fitness: fitness.iter().copied().max().unwrap_or(0),
+            mean_fitness: stats::mean(&values),
+            median_fitness: stats::median(&values),
+            fitness,
+            diversity: evolver.diversity(),
+            mutation: evolver.mutator().as_ref().map(|m| m.describe()).unwrap_or_default(),
+            benchmarks: vec![],
+            generation_time_secs: generation_time.as_secs_f64(),
+        }
+    }
+
+    pub fn add_benchmark(&mut self, benchmark: BenchmarkRecord) {
+        self.benchmarks.push(benchmark);
+    }
+
+    fn csv_header(opponents: &[&str]) -> String {
+        let mut columns = vec!["generation", "island", "best_fitness", "mean_fitness", "median_fitness", "diversity", "mutation", "generation_time_secs", "fitness"].into_iter().map(String::from).collect::<Vec<_>>();
+        for opponent in opponents {
+            for column in ["wins", "draws", "losses", "win_rate", "win_rate_low", "win_rate_high"] {
+                columns.push(format!("{}_{}", opponent, column));
+            }
+        }
+        columns.join(",")
+    }
+
+    // The benchmark columns follow `opponents`, they are empty for an opponent without a benchmark
+    fn csv_row(&self, opponents: &[&str]) -> String {
+        let fitness = self.fitness.iter().map(|f| f.to_string()).collect::<Vec<_>>().join(";");
+        let mut columns = vec![
+            self.generation.to_string(),
+            self.island.to_string(),
+            self.best_fitness.to_string(),
+            self.mean_fitness.to_string(),
+            self.median_fitness.to_string(),
+            self.diversity.to_string(),
+            format!("\"{}\"", self.mutation.replace('"', "\"\"")),
+            self.generation_time_secs.to_string(),
+            fitness,
+        ];
+        for opponent in opponents {
+            match self.benchmarks.iter().find(|b| b.opponent == *opponent) {
+                Some(benchmark) => {
+                    columns.push(benchmark.wins.to_string());
+                    columns.push(benchmark.draws.to_string());
+                    columns.push(benchmark.losses.to_string());
+                    columns.push(benchmark.win_rate.to_string());
+                    columns.push(benchmark.win_rate_low.to_string());
+                    columns.push(benchmark.win_rate_high.to_string());
+                }
+                None => columns.extend(std::iter::repeat_n(String::new(), 6)),
+            }
+        }
+        columns.join(",")
+    }
+}
+
+pub struct MetricsWriter {
+    csv: BufWriter<File>,
+    jsonl: BufWriter<File>,
+    opponents: Vec<&'static str>,
+}
+
+impl MetricsWriter {
+    // Creates `<prefix>.csv` and `<prefix>.jsonl`, the CSV has benchmark columns for each of `opponents`
+    pub fn create<P: AsRef<Path>>(prefix: P, opponents: &[&'static str]) -> io::Result<Self> {
+        let prefix = prefix.as_ref();
+        let mut csv = BufWriter::new(File::create(prefix.with_extension("csv"))?);
+        writeln!(csv, "{}", GenerationRecord::csv_header(opponents))?;
+        Ok(MetricsWriter {
+            csv,
+            jsonl: BufWriter::new(File::create(prefix.with_extension("jsonl"))?),
+            opponents: opponents.to_vec(),
+        })
+    }
+
+    pub fn write(&mut self, record: &GenerationRecord) -> io::Result<()> {
+        writeln!(self.csv, "{}", record.csv_row(&self.opponents))?;
+        serde_json::to_writer(&mut self.jsonl, record)?;
+        writeln!(self.jsonl)?;
+
+        self.csv.flush()?;
+        self.jsonl.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BenchmarkRecord, GenerationRecord};
+
+    fn record() -> GenerationRecord {
+        GenerationRecord {
+            generation: 3,
+            island: 1,
+            fitness: vec![4, 2, 0],
+            best_fitness: 4,
+            mean_fitness: 2.0,
+            median_fitness: 2.0,
+            diversity: 0.5,
+            mutation: "probability=0.1 range=-1..1".to_string(),
+            benchmarks: vec![BenchmarkRecord { opponent: "random".to_string(), games: 10, wins: 6, draws: 1, losses: 3, win_rate: 0.6, win_rate_low: 0.3, win_rate_high: 0.8 }],
+            generation_time_secs: 1.5,
+        }
+    }
+
+    #[test]
+    fn csv_columns_match_header() {
+        let record = record();
+        let header = GenerationRecord::csv_header(&["random"]);
+        let row = record.csv_row(&["random"]);
+        assert!(header.ends_with("random_win_rate_high"));
+        assert_eq!(header.split(',').count(), row.split(',').count());
+        assert!(row.starts_with("3,1,4,2,2,0.5,\"probability=0.1 range=-1..1\",1.5,4;2;0,6,1,3,0.6"));
+
+        // Columns stay in the order of the header whatever benchmarks a record has
+        let header = GenerationRecord::csv_header(&["rule_based", "random"]);
+        let row = record.csv_row(&["rule_based", "random"]);
+        assert_eq!(header.split(',').count(), row.split(',').count());
+        assert!(row.ends_with("4;2;0,,,,,,,6,1,3,0.6,0.3,0.8"));
+    }
+
+    #[test]
+    fn json_line() {
+        let json = serde_json::to_string(&record()).unwrap();
+        assert!(json.contains("\"opponent\":\"random\""));
+        assert!(!json.contains('\n'));
+    }
+}
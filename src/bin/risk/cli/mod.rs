@@ -0,0 +1,142 @@
+use std::io::Write;
+use std::path::PathBuf;
+
+use clap::{Args, Parser, Subcommand};
+use once_cell::sync::OnceCell;
+
+use risk::adjudicator::Adjudicator;
+use risk::arena::{ArenaOptions, CancelHandle};
+use risk::game::TimeoutPenalty;
+
+use config::Config;
+
+pub mod config;
+#[cfg(feature = "training")]
+mod train;
+mod games;
+mod replay;
+mod server;
+#[cfg(feature = "training")]
+mod metrics;
+
+#[derive(Parser)]
+#[command(name = "risk", about = "Play, train and evaluate Risk bots")]
+pub struct Cli {
+    #[arg(short, long, global = true, help = "TOML file with the settings of all subcommands, command line options take precedence")]
+    config: Option<PathBuf>,
+
+    #[command(flatten)]
+    game: GameArgs,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    #[cfg(feature = "training")]
+    #[command(about = "Evolve neural or rule based bots on islands and benchmark the best genomes")]
+    Train(train::TrainArgs),
+    #[command(about = "Play one game between two bots")]
+    Play(games::PlayArgs),
+    #[command(about = "Play many games between two bots")]
+    Arena(games::ArenaArgs),
+    #[command(about = "Play every bot against the others and rank them")]
+    Tournament(games::TournamentArgs),
+    #[command(about = "Step through a recorded game")]
+    Replay(replay::ReplayArgs),
+    #[cfg(feature = "render")]
+    #[command(about = "Draw the map of a saved game state or game record")]
+    Render(replay::RenderArgs),
+    #[command(about = "Aggregate statistics of recorded games")]
+    Analyze(replay::AnalyzeArgs),
+    #[command(about = "Host games for clients that connect over TCP")]
+    Serve(server::ServeArgs),
+    #[command(about = "Join a game on a server with a local bot or as a human")]
+    Connect(server::ConnectArgs),
+}
+
+#[derive(Args)]
+struct GameArgs {
+    #[arg(long, global = true)]
+    max_rounds: Option<u16>,
+    #[arg(long, global = true)]
+    max_moves_per_turn: Option<usize>,
+    #[arg(long, global = true, help = "How games that hit the round limit are decided, e.g. territories or weighted:armies=1")]
+    adjudicator: Option<Adjudicator>,
+    #[arg(long, global = true, help = "Time limit for every move in milliseconds")]
+    move_ms: Option<u64>,
+    #[arg(long, global = true, help = "Clock of each player for the whole game in milliseconds")]
+    game_ms: Option<u64>,
+    #[arg(long, global = true, help = "Added to the clock after every move in milliseconds")]
+    increment_ms: Option<u64>,
+    #[arg(long, global = true, help = "What happens when a bot runs out of time: forfeit or random_move")]
+    timeout_penalty: Option<TimeoutPenalty>,
+}
+
+pub fn run() -> Result<(), String> {
+    let cli = Cli::parse();
+
+    let mut config = match &cli.config {
+        Some(path) => Config::load(path)?,
+        None => Config::default(),
+    };
+    if let Some(max_rounds) = cli.game.max_rounds {
+        config.game.max_rounds = max_rounds;
+    }
+    if let Some(max_moves_per_turn) = cli.game.max_moves_per_turn {
+        config.game.max_moves_per_turn = max_moves_per_turn;
+    }
+    if let Some(adjudicator) = cli.game.adjudicator {
+        config.game.adjudicator = adjudicator;
+    }
+    let time_control = &mut config.game.time_control;
+    if cli.game.move_ms.is_some() {
+        time_control.move_ms = cli.game.move_ms;
+    }
+    if cli.game.game_ms.is_some() {
+        time_control.game_ms = cli.game.game_ms;
+    }
+    if let Some(increment_ms) = cli.game.increment_ms {
+        time_control.increment_ms = increment_ms;
+    }
+    if let Some(penalty) = cli.game.timeout_penalty {
+        time_control.penalty = penalty;
+    }
+
+    match cli.command {
+        #[cfg(feature = "training")]
+        Command::Train(args) => train::run(args, config),
+        Command::Play(args) => games::play(args, config),
+        Command::Arena(args) => games::arena(args, config),
+        Command::Tournament(args) => games::tournament(args, config),
+        Command::Replay(args) => replay::replay(args),
+        #[cfg(feature = "render")]
+        Command::Render(args) => replay::render(args),
+        Command::Analyze(args) => replay::analyze(args),
+        Command::Serve(args) => server::serve(args, config),
+        Command::Connect(args) => server::connect(args, config),
+    }
+}
+
+// Running games are finished on Ctrl-C, no new ones are started. The handler can only be installed once per process,
+// so every caller gets a clone of the same handle.
+fn cancel_on_ctrl_c() -> CancelHandle {
+    static CANCEL: OnceCell<CancelHandle> = OnceCell::new();
+    CANCEL.get_or_init(|| {
+        let cancel = CancelHandle::new();
+        let handle = cancel.clone();
+        ctrlc::set_handler(move || {
+            println!("\nStopping, waiting for running games to finish");
+            handle.cancel();
+        }).expect("Could not install Ctrl-C handler");
+        cancel
+    }).clone()
+}
+
+fn arena_options(config: &Config, cancel: &CancelHandle) -> ArenaOptions {
+    ArenaOptions::default().game_config(config.game.game_config()).cancel_handle(cancel.clone()).on_progress(|progress| {
+        print!("\r{}   ", progress);
+        std::io::stdout().flush().ok();
+    })
+}
@@ -0,0 +1,167 @@
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use clap::Args;
+use strum::IntoEnumIterator;
+
+use risk::game::GameResult;
+use risk::game_record::GameRecord;
+#[cfg(feature = "render")]
+use risk::game_state::DrawMapOptions;
+use risk::game_state::GameState;
+use risk::game_stats::{AggregateStats, GameStats};
+use risk::player::Player;
+
+#[derive(Args)]
+pub struct ReplayArgs {
+    record: PathBuf,
+    #[arg(long, default_value_t = 0, help = "Pause between rounds in milliseconds")]
+    delay: u64,
+    #[arg(long, help = "Draw the map after every round into this folder")]
+    images: Option<String>,
+    #[arg(long, help = "Print the territories after every round")]
+    states: bool,
+}
+
+#[cfg(feature = "render")]
+#[derive(Args)]
+pub struct RenderArgs {
+    #[arg(help = "Game record or a single game state as JSON")]
+    file: PathBuf,
+    #[arg(long, help = "Round of a game record to draw, defaults to the end of the game")]
+    round: Option<u16>,
+    #[arg(short, long, default_value = "output-map.png")]
+    output: String,
+    #[arg(long, help = "Also print the map to the terminal")]
+    print: bool,
+    #[arg(long, help = "PNG to draw the territories on instead of the built in board")]
+    background: Option<String>,
+}
+
+#[derive(Args)]
+pub struct AnalyzeArgs {
+    #[arg(required = true, help = "Game records (.json) or per game statistics written by the arena (.jsonl)")]
+    files: Vec<PathBuf>,
+    #[arg(short, long, help = "Write the aggregated statistics to this JSON file")]
+    output: Option<String>,
+}
+
+pub fn replay(args: ReplayArgs) -> Result<(), String> {
+    let record = GameRecord::load(&args.record).map_err(|e| format!("Could not load {}: {}", args.record.display(), e))?;
+    if let Some(folder) = &args.images {
+        fs::create_dir_all(folder).map_err(|e| format!("Could not create {}: {}", folder, e))?;
+    }
+
+    println!("{} vs {}", record.bot_a, record.bot_b);
+    draw_round(&args, 0, &record.initial_state)?;
+    for (idx, m) in record.moves.iter().enumerate() {
+        if idx == 0 || record.moves[idx - 1].round != m.round {
+            println!("Round {} ({})", m.round, m.player);
+        }
+        println!("  {:?}", m.move_played);
+
+        if record.moves.get(idx + 1).map(|next| next.round) != Some(m.round) {
+            if args.states {
+                println!("{}", m.state);
+            }
+            draw_round(&args, m.round, &m.state)?;
+            thread::sleep(Duration::from_millis(args.delay));
+        }
+    }
+
+    match record.result {
+        Some(GameResult::Win(player)) => println!("{} won after {} rounds", player, record.rounds),
+        Some(GameResult::Draw) => println!("Draw after {} rounds", record.rounds),
+        None => println!("Unfinished after {} rounds", record.rounds),
+    }
+    Ok(())
+}
+
+#[cfg(feature = "render")]
+fn draw_round(args: &ReplayArgs, round: u16, state: &GameState) -> Result<(), String> {
+    if let Some(folder) = &args.images {
+        let filename = format!("{}/{}.png", folder, round);
+        state.draw_map(DrawMapOptions::default().filename(&filename)).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "render"))]
+fn draw_round(args: &ReplayArgs, _round: u16, _state: &GameState) -> Result<(), String> {
+    match &args.images {
+        Some(folder) => Err(format!("Can not draw the map into {}, built without the render feature", folder)),
+        None => Ok(()),
+    }
+}
+
+#[cfg(feature = "render")]
+pub fn render(args: RenderArgs) -> Result<(), String> {
+    let content = fs::read_to_string(&args.file).map_err(|e| format!("Could not read {}: {}", args.file.display(), e))?;
+    let state = match serde_json::from_str::<GameRecord>(&content) {
+        Ok(record) => args.round.map_or(record.final_state(), |round| record.state_after_round(round)),
+        Err(_) => serde_json::from_str::<GameState>(&content).map_err(|e| format!("{} is neither a game record nor a game state: {}", args.file.display(), e))?,
+    };
+
+    let mut options = DrawMapOptions::default().filename(&args.output);
+    if let Some(background) = &args.background {
+        options = options.background(background);
+    }
+    #[cfg(feature = "terminal-preview")]
+    let options = if args.print { options.should_print() } else { options };
+    #[cfg(not(feature = "terminal-preview"))]
+    if args.print {
+        return Err("Can not print the map, built without the terminal-preview feature".to_string());
+    }
+    state.draw_map(options).map_err(|e| e.to_string())
+}
+
+pub fn analyze(args: AnalyzeArgs) -> Result<(), String> {
+    let mut games: Vec<GameStats> = vec![];
+    let mut results: Vec<GameResult> = vec![];
+    for path in &args.files {
+        if path.extension().is_some_and(|extension| extension == "jsonl") {
+            games.extend(load_game_stats(path)?);
+        } else {
+            let record = GameRecord::load(path).map_err(|e| format!("Could not load {}: {}", path.display(), e))?;
+            games.push(record.stats());
+            results.extend(record.result);
+        }
+    }
+
+    let aggregate = AggregateStats::from_games(&games);
+    println!("Games: {}, average rounds: {:.1}", aggregate.games, aggregate.avg_rounds);
+    if !results.is_empty() {
+        let wins = |player| results.iter().filter(|&&r| r == GameResult::Win(player)).count();
+        println!("Results of {} records: {} wins A, {} wins B, {} draws", results.len(), wins(Player::A), wins(Player::B), results.iter().filter(|&&r| r == GameResult::Draw).count());
+    }
+    for player in Player::iter() {
+        let stats = &aggregate.players[player as usize];
+        println!("{}: {:.1} reinforcements, {:.1} attacks ({:.0}% won), {:.1} fortifications, {:.1} armies lost, largest stack {:.1}",
+            player, stats.avg_reinforce_moves, stats.avg_attack_moves, stats.attack_success_rate * 100.0, stats.avg_fortify_moves, stats.avg_armies_lost, stats.avg_largest_stack);
+        // Records do not keep the thinking time, only the statistics written by the arena do
+        if stats.max_move_time_ms > 0.0 {
+            println!("  {:.2} ms per move, longest move {:.1} ms, {:.1} timeouts per game", stats.avg_move_time_ms, stats.max_move_time_ms, stats.avg_timeouts);
+        }
+        for (continent, rate) in &stats.continent_completion_rate {
+            if let Some(round) = stats.avg_continent_completion_round.get(continent) {
+                println!("  {:14} completed in {:.0}% of games, on average in round {:.1}", format!("{:?}", continent), rate * 100.0, round);
+            }
+        }
+    }
+
+    if let Some(path) = &args.output {
+        aggregate.write_json(path).map_err(|e| format!("Could not write {}: {}", path, e))?;
+    }
+    Ok(())
+}
+
+fn load_game_stats(path: &Path) -> Result<Vec<GameStats>, String> {
+    let file = File::open(path).map_err(|e| format!("Could not open {}: {}", path.display(), e))?;
+    BufReader::new(file).lines().enumerate().filter(|(_, line)| line.as_ref().map_or(true, |l| !l.trim().is_empty())).map(|(idx, line)| {
+        let line = line.map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+        serde_json::from_str(&line).map_err(|e| format!("{}:{}: {}", path.display(), idx + 1, e))
+    }).collect()
+}
@@ -0,0 +1,89 @@
+use std::cell::Cell;
+use std::time::Duration;
+
+use clap::Args;
+
+use risk::bots::registry::BotRegistry;
+use risk::game::GameResult;
+use risk::player::Player;
+use risk::protocol::Request;
+use risk::server::{client, Server};
+
+use super::config::Config;
+
+#[derive(Args)]
+pub struct ServeArgs {
+    #[arg(long, help = "Address to listen on, e.g. 0.0.0.0:7777")]
+    address: Option<String>,
+    #[arg(short, long, help = "Stop after this many games")]
+    games: Option<u32>,
+    #[arg(long, help = "Time per move in milliseconds before a client forfeits")]
+    timeout_ms: Option<u64>,
+}
+
+#[derive(Args)]
+pub struct ConnectArgs {
+    #[arg(help = "Address of the server, defaults to the address in [server]")]
+    address: Option<String>,
+    #[arg(long, default_value = "human", help = "Bot that plays on this side, e.g. human or external:<command>")]
+    bot: String,
+    #[arg(long, help = "Name shown to the opponent, defaults to the bot")]
+    name: Option<String>,
+    #[arg(short, long, help = "Print every move of the game")]
+    verbose: bool,
+}
+
+pub fn serve(args: ServeArgs, config: Config) -> Result<(), String> {
+    let mut server_config = config.server.clone();
+    if let Some(address) = args.address {
+        server_config.address = address;
+    }
+    if args.games.is_some() {
+        server_config.games = args.games;
+    }
+    if let Some(timeout_ms) = args.timeout_ms {
+        server_config.timeout_ms = timeout_ms;
+    }
+
+    let server = Server::bind(&server_config.address).map_err(|e| format!("Could not listen on {}: {}", server_config.address, e))?
+        .config(config.game.game_config())
+        .adjudicator(config.game.adjudicator)
+        .timeout(Duration::from_millis(server_config.timeout_ms));
+    println!("Listening on {}", server_config.address);
+
+    server.run(server_config.games, |hosted| match hosted {
+        Ok(hosted) => {
+            let [a, b] = &hosted.players;
+            match &hosted.outcome {
+                Ok(GameResult::Win(player)) => println!("{} vs {}: {} won after {} rounds", a, b, if *player == Player::A { a } else { b }, hosted.rounds),
+                Ok(GameResult::Draw) => println!("{} vs {}: draw after {} rounds", a, b, hosted.rounds),
+                Err(err) => println!("{} vs {}: {}", a, b, err),
+            }
+        }
+        Err(err) => println!("Game failed: {}", err),
+    }).map_err(|e| format!("Server stopped: {}", e))
+}
+
+pub fn connect(args: ConnectArgs, config: Config) -> Result<(), String> {
+    let address = args.address.unwrap_or(config.server.address);
+    let bot = BotRegistry::default().create(&args.bot)?;
+    let name = args.name.unwrap_or(args.bot);
+
+    let seat = Cell::new(None);
+    let (player, result) = client::play(&address, &name, bot, |request| match request {
+        Request::NewGame { player, opponent, .. } => {
+            seat.set(Some(*player));
+            println!("Playing as {} against {}", player, opponent.as_deref().unwrap_or("unknown"));
+        }
+        Request::Update { player, move_played, .. } if args.verbose || seat.get() != Some(*player) => println!("{}: {:?}", player, move_played),
+        Request::GameOver { reason: Some(reason), .. } => println!("{}", reason),
+        _ => {}
+    })?;
+
+    match result {
+        GameResult::Win(winner) if winner == player => println!("You won"),
+        GameResult::Win(_) => println!("You lost"),
+        GameResult::Draw => println!("Draw"),
+    }
+    Ok(())
+}
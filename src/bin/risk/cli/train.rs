@@ -0,0 +1,261 @@
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use clap::Args;
+use ndarray::{Array1, ArrayView1};
+
+use risk::adjudicator::Adjudicator;
+use risk::arena::{self, ArenaOptions, CancelHandle};
+use risk::bots::neural_bot::NeuralBot;
+use risk::bots::random_bot::RandomBot;
+use risk::bots::rule_based_bot::{RuleBasedBot, RuleWeights};
+use risk::bots::{Bot, Float};
+use risk::evolver::island::IslandEvolver;
+use risk::evolver::rule_weights::RuleWeightsEvaluator;
+use risk::evolver::{transformations, EvaluationResult, Evaluator, Evolver, ProbabilityMutator};
+use super::metrics::{BenchmarkRecord, GenerationRecord, MetricsWriter};
+use risk::player::Player;
+
+use risk::tournament::BotFactory;
+
+use super::config::{Config, TrainConfig};
+
+const POPULATION: usize = 40;
+
+// Combines two parent genomes into a child
+type Transformation<const LENGTH: usize> = fn(&[Float; LENGTH], &[Float; LENGTH]) -> [Float; LENGTH];
+
+#[derive(Args)]
+pub struct TrainArgs {
+    #[arg(long, help = "Bot to train: neural or rule_based")]
+    bot: Option<String>,
+    #[arg(long)]
+    generations: Option<usize>,
+    #[arg(long)]
+    islands: Option<usize>,
+    #[arg(long)]
+    migration_interval: Option<usize>,
+    #[arg(long)]
+    migrants: Option<usize>,
+    #[arg(long)]
+    evaluation_games: Option<u32>,
+    #[arg(long)]
+    evaluation_adjudicator: Option<Adjudicator>,
+    #[arg(long)]
+    benchmark_games: Option<u32>,
+    #[arg(long, help = "Prefix of the CSV and JSONL metrics files")]
+    metrics: Option<String>,
+    #[arg(long)]
+    weights_dir: Option<String>,
+}
+
+struct Eval<const LENGTH: usize> {
+    games: u32,
+    adjudicator: Adjudicator,
+    options: ArenaOptions,
+}
+
+impl<const LENGTH: usize> Evaluator<LENGTH> for Eval<LENGTH> {
+    fn initialize(&mut self) -> [Float; LENGTH] {
+        NeuralBot::get_random_weights_and_biases().to_vec().try_into().unwrap()
+    }
+
+    fn evaluate(&self, a: &[Float], b: &[Float]) -> EvaluationResult {
+        let adjudicator = self.adjudicator;
+        let result = arena::play_games_with_options::<NeuralBot, NeuralBot, _, _, _>(self.games, move |state| adjudicator.adjudicate(state), || NeuralBot::from_weights_and_biases(&Array1::from(a.to_owned())), || NeuralBot::from_weights_and_biases(&Array1::from(b.to_owned())), &self.options).unwrap();
+        match result.winner() {
+            Some(Player::A) => EvaluationResult::A,
+            Some(Player::B) => EvaluationResult::B,
+            None => EvaluationResult::Draw,
+        }
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.options.is_cancelled()
+    }
+}
+
+// Counts the evaluations of all islands of a generation, the islands are evaluated in parallel
+#[derive(Clone, Default)]
+struct EvaluationProgress(Arc<AtomicUsize>);
+
+impl EvaluationProgress {
+    fn reporter(&self, islands: usize) -> impl Fn(usize, usize) + Send + 'static {
+        let evaluated = self.0.clone();
+        move |_, total| {
+            let evaluated = evaluated.fetch_add(1, Ordering::SeqCst) + 1;
+            print!("\rEvaluated {}/{} matchups   ", evaluated, total * islands);
+            std::io::stdout().flush().ok();
+        }
+    }
+
+    fn reset(&self) {
+        self.0.store(0, Ordering::SeqCst);
+    }
+}
+
+pub fn run(args: TrainArgs, config: Config) -> Result<(), String> {
+    let mut train = config.train.clone();
+    if let Some(generations) = args.generations {
+        train.generations = generations;
+    }
+    if let Some(islands) = args.islands {
+        train.islands = islands;
+    }
+    if let Some(migration_interval) = args.migration_interval {
+        train.migration_interval = migration_interval;
+    }
+    if let Some(migrants) = args.migrants {
+        train.migrants = migrants;
+    }
+    if let Some(evaluation_games) = args.evaluation_games {
+        train.evaluation_games = evaluation_games;
+    }
+    if let Some(evaluation_adjudicator) = args.evaluation_adjudicator {
+        train.evaluation_adjudicator = evaluation_adjudicator;
+    }
+    if let Some(benchmark_games) = args.benchmark_games {
+        train.benchmark_games = benchmark_games;
+    }
+    if let Some(metrics) = args.metrics {
+        train.metrics = metrics;
+    }
+    if args.weights_dir.is_some() {
+        train.weights_dir = args.weights_dir;
+    }
+    if let Some(bot) = args.bot {
+        train.bot = bot;
+    }
+    if train.islands == 0 {
+        return Err("Training needs at least one island".to_string());
+    }
+    if train.migration_interval == 0 {
+        return Err("The migration interval has to be at least one generation".to_string());
+    }
+    if let Some(dir) = &train.weights_dir {
+        fs::create_dir_all(dir).map_err(|e| format!("Could not create {}: {}", dir, e))?;
+    }
+
+    let cancel = super::cancel_on_ctrl_c();
+    let evaluation_options = ArenaOptions::default().game_config(config.game.game_config()).cancel_handle(cancel.clone());
+    let progress = EvaluationProgress::default();
+
+    match train.bot.as_str() {
+        "neural" => {
+            let transformations: [Transformation<{ NeuralBot::LENGTH }>; 3] = [transformations::select, transformations::average, transformations::select_half];
+            let islands = (0..train.islands).map(|idx| {
+                let evaluator = Eval::<{ NeuralBot::LENGTH }> { games: train.evaluation_games, adjudicator: train.evaluation_adjudicator, options: evaluation_options.clone() };
+                Evolver::with_transformation(evaluator, Box::new(transformations[idx % transformations.len()])).on_progress(progress.reporter(train.islands))
+            }).collect();
+            evolve(&train, &config, &cancel, &progress, islands, Training {
+                mutator: |g, idx| {
+                    let range = 1.0 + idx as Float * 0.5;
+                    ProbabilityMutator { probability: 1.0/(g as f64 * 100.0) + 0.001, range: -range..range }
+                },
+                save: |genome: &[Float; NeuralBot::LENGTH], path: &Path| NeuralBot::save_weights_and_biases(genome, path.with_extension("bin")).map_err(|e| e.to_string()),
+                factory: |genome: &[Float; NeuralBot::LENGTH]| {
+                    let weights = ArrayView1::from(genome).to_owned();
+                    Box::new(move || Box::new(NeuralBot::from_weights_and_biases(&weights)) as Box<dyn Bot>) as BotFactory
+                },
+            })
+        }
+        // Only a handful of weights, so every gene is mutated more often than in the neural network
+        "rule_based" | "rule" => {
+            let islands = (0..train.islands).map(|idx| {
+                let evaluator = RuleWeightsEvaluator::new(train.evaluation_games, train.evaluation_adjudicator, evaluation_options.clone());
+                Evolver::with_transformation(evaluator, Box::new(if idx % 2 == 0 { transformations::select } else { transformations::average })).on_progress(progress.reporter(train.islands))
+            }).collect();
+            evolve(&train, &config, &cancel, &progress, islands, Training {
+                mutator: |g, idx| ProbabilityMutator { probability: 0.3 / g as f64 + 0.05, range: 0.0..(2.0 + idx as Float * 0.5) },
+                save: |genome: &[Float; RuleWeights::LENGTH], path: &Path| RuleWeights::from_genome(genome).save(path.with_extension("toml")),
+                factory: |genome: &[Float; RuleWeights::LENGTH]| {
+                    let weights = RuleWeights::from_genome(genome);
+                    Box::new(move || Box::new(RuleBasedBot::new(weights)) as Box<dyn Bot>) as BotFactory
+                },
+            })
+        }
+        bot => Err(format!("Can not train '{}', expected neural or rule_based", bot)),
+    }
+}
+
+// What differs between the bots that can be trained
+struct Training<M, S, F> {
+    // Mutator of an island in a generation
+    mutator: M,
+    // Writes a genome, the extension of the path is set by the bot
+    save: S,
+    factory: F,
+}
+
+fn evolve<E, M, S, F, const LENGTH: usize>(train: &TrainConfig, config: &Config, cancel: &CancelHandle, progress: &EvaluationProgress, islands: Vec<Evolver<E, ProbabilityMutator, LENGTH, POPULATION>>, training: Training<M, S, F>) -> Result<(), String>
+where
+E: Evaluator<LENGTH> + Send,
+M: Fn(usize, usize) -> ProbabilityMutator,
+S: Fn(&[Float; LENGTH], &Path) -> Result<(), String>,
+F: Fn(&[Float; LENGTH]) -> BotFactory {
+    let benchmark_options = super::arena_options(config, cancel);
+    let adjudicator = config.game.adjudicator;
+    let mut evolver = IslandEvolver::new(islands, train.migration_interval, train.migrants);
+    let mut metrics = MetricsWriter::create(&train.metrics, &["random", "rule_based"]).map_err(|e| format!("Could not create metrics files: {}", e))?;
+
+    for g in 1..=train.generations {
+        let generation_start = Instant::now();
+        println!("Generation: {}", g);
+
+        for (idx, island) in evolver.islands_mut().iter_mut().enumerate() {
+            island.set_mutator(Some((training.mutator)(g, idx)));
+        }
+
+        progress.reset();
+        let Some(best_genomes) = evolver.evolve_step() else {
+            break;
+        };
+        let generation_time = generation_start.elapsed();
+        println!();
+        if cancel.is_cancelled() {
+            break;
+        }
+
+        for (idx, best_genome) in best_genomes.iter().enumerate() {
+            let mut record = GenerationRecord::from_evolver(g, idx, &evolver.islands()[idx], generation_time);
+
+            if let Some(dir) = &train.weights_dir {
+                let path = Path::new(dir).join(format!("island_{}", idx));
+                (training.save)(best_genome, &path).map_err(|e| format!("Could not save {}: {}", path.display(), e))?;
+            }
+
+            let factory = (training.factory)(best_genome);
+            let results = arena::play_games_with_options(train.benchmark_games, move |state| adjudicator.adjudicate(state), || RandomBot {}, &factory, &benchmark_options)?;
+            println!("\rIsland {}: Against Random Bot {:?}", idx, results);
+            record.add_benchmark(BenchmarkRecord::from_arena_result("random", &results, Player::B));
+
+            let results = arena::play_games_with_options(train.benchmark_games, move |state| adjudicator.adjudicate(state), RuleBasedBot::default, &factory, &benchmark_options)?;
+            println!("\rIsland {}: Against Rule Based Bot {:?}", idx, results);
+            record.add_benchmark(BenchmarkRecord::from_arena_result("rule_based", &results, Player::B));
+
+            metrics.write(&record).map_err(|e| format!("Could not write metrics: {}", e))?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::config::Config;
+    use super::{run, TrainArgs};
+
+    #[test]
+    fn rejects_invalid_islands() {
+        let args = |islands, migration_interval| TrainArgs {
+            bot: None, generations: Some(1), islands: Some(islands), migration_interval: Some(migration_interval), migrants: None,
+            evaluation_games: None, evaluation_adjudicator: None, benchmark_games: None, metrics: None, weights_dir: None,
+        };
+        assert!(run(args(0, 1), Config::default()).unwrap_err().contains("island"));
+        assert!(run(args(1, 0), Config::default()).unwrap_err().contains("migration interval"));
+    }
+}
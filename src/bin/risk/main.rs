@@ -0,0 +1,12 @@
+// #![allow(dead_code)]
+// #![allow(unused_imports)]
+
+mod cli;
+mod tui;
+
+fn main() {
+    if let Err(err) = cli::run() {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    }
+}
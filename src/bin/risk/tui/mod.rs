@@ -0,0 +1,167 @@
+use std::io;
+use std::sync::mpsc::Receiver;
+use std::time::Duration;
+
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+
+use risk::bots::Bot;
+use risk::game::{Game, GameResult};
+use risk::game_state::{GameState, Move};
+use risk::game_stats::GameStats;
+use risk::player::Player;
+
+use runner::{Console, GameEvent, GameOutcome, Playback};
+
+pub mod runner;
+mod ui;
+
+const LOG_LENGTH: usize = 500;
+
+// Everything the UI shows, updated from the events of the game thread
+pub struct App {
+    names: [String; 2],
+    state: GameState,
+    stats: GameStats,
+    round: u16,
+    last_move: Option<(Player, Move)>,
+    log: Vec<String>,
+    status: Option<String>,
+    playback: Playback,
+    console: Option<Console>,
+    console_lines: Vec<String>,
+    input: String,
+}
+
+impl App {
+    pub fn new(names: [String; 2], state: GameState, playback: Playback, console: Option<Console>) -> Self {
+        Self {
+            names,
+            state,
+            stats: GameStats::new(&state),
+            round: 0,
+            last_move: None,
+            log: vec![],
+            status: None,
+            playback,
+            console,
+            console_lines: vec![String::new()],
+            input: String::new(),
+        }
+    }
+
+    fn name(&self, player: Player) -> &str {
+        &self.names[player as usize]
+    }
+
+    pub fn apply(&mut self, event: GameEvent) {
+        match event {
+            GameEvent::Moved { round, player, move_played, state, stats } => {
+                self.log.push(format!("{:>3} {} {:?}", round, if player == Player::A { "A" } else { "B" }, move_played));
+                if self.log.len() > LOG_LENGTH {
+                    self.log.remove(0);
+                }
+                self.round = round;
+                self.last_move = Some((player, move_played));
+                self.state = state;
+                self.stats = *stats;
+            }
+            GameEvent::Finished(GameResult::Win(player)) => self.status = Some(format!("{} ({}) won after {} rounds", player, self.name(player), self.round)),
+            GameEvent::Finished(GameResult::Draw) => self.status = Some(format!("Draw after {} rounds", self.round)),
+            GameEvent::Failed(message) => self.status = Some(message),
+        }
+    }
+
+    // The bot writes its prompt without a newline, so output is appended to the last line
+    fn push_console(&mut self, text: &str) {
+        let mut lines = text.split('\n');
+        if let Some(first) = lines.next() {
+            self.console_lines.last_mut().unwrap().push_str(first);
+        }
+        self.console_lines.extend(lines.map(|line| line.to_string()));
+        if self.console_lines.len() > LOG_LENGTH {
+            self.console_lines.drain(..self.console_lines.len() - LOG_LENGTH);
+        }
+    }
+
+    // Returns false to quit. While a human plays, letters go to the input line, so the playback
+    // keys move to the arrow keys and Ctrl.
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Esc => return false,
+            KeyCode::Char('c') if ctrl => return false,
+            KeyCode::Char('p') if ctrl => self.playback.toggle_pause(),
+            KeyCode::Up => self.playback.faster(),
+            KeyCode::Down => self.playback.slower(),
+            KeyCode::Right => self.playback.step(),
+            _ if self.console.is_some() => self.edit_input(key.code),
+            KeyCode::Char('q') => return false,
+            KeyCode::Char(' ') | KeyCode::Char('p') => self.playback.toggle_pause(),
+            KeyCode::Char('n') => self.playback.step(),
+            KeyCode::Char('+') => self.playback.faster(),
+            KeyCode::Char('-') => self.playback.slower(),
+            _ => {}
+        }
+        true
+    }
+
+    fn edit_input(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Char(c) => self.input.push(c),
+            KeyCode::Backspace => {
+                self.input.pop();
+            }
+            KeyCode::Enter => {
+                let line = std::mem::take(&mut self.input);
+                self.push_console(&format!("{}\n", line));
+                if let Some(console) = &self.console {
+                    console.input.send(format!("{}\n", line)).ok();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn receive(&mut self, events: &Receiver<GameEvent>) {
+        while let Ok(event) = events.try_recv() {
+            self.apply(event);
+        }
+        let output = self.console.as_ref().map(|console| console.output.try_iter().collect::<String>()).unwrap_or_default();
+        if !output.is_empty() {
+            self.push_console(&output);
+        }
+    }
+}
+
+// Shows the game full screen until it is quit. The game keeps its bots, stats and record and is handed back at the end.
+pub fn run<BotA, BotB, Evaluator>(game: Game<BotA, BotB>, names: [String; 2], evaluate_result: Evaluator, delay: Duration, console: Option<Console>) -> io::Result<(Game<BotA, BotB>, GameOutcome)>
+where
+BotA: Bot + 'static,
+BotB: Bot + 'static,
+Evaluator: Fn(GameState) -> GameResult + Send + 'static {
+    let playback = Playback::new(delay);
+    let mut app = App::new(names, *game.state(), playback.clone(), console);
+    let (events, handle) = runner::spawn_game(game, evaluate_result, playback.clone());
+
+    let mut terminal = ratatui::init();
+    let result = (|| -> io::Result<()> {
+        loop {
+            app.receive(&events);
+            terminal.draw(|frame| ui::draw(frame, &app))?;
+            if event::poll(Duration::from_millis(30))? {
+                if let Event::Key(key) = event::read()? {
+                    if key.kind == KeyEventKind::Press && !app.handle_key(key) {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+    })();
+    ratatui::restore();
+
+    // Dropping the console ends a human turn that is still waiting for input
+    playback.stop();
+    drop(app);
+    result?;
+    handle.join().map_err(|_| io::Error::other("Game thread panicked"))
+}
@@ -0,0 +1,243 @@
+use std::io::{self, BufReader, Read, Write};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use risk::bots::human_bot::HumanBot;
+use risk::bots::Bot;
+use risk::game::{Game, GameError, GameResult};
+use risk::game_state::{GameState, Move};
+use risk::game_stats::GameStats;
+use risk::player::Player;
+
+pub enum GameEvent {
+    Moved { round: u16, player: Player, move_played: Move, state: GameState, stats: Box<GameStats> },
+    Finished(GameResult),
+    Failed(String),
+}
+
+struct PlaybackState {
+    paused: bool,
+    steps: u32,
+    delay: Duration,
+    stopped: bool,
+}
+
+// Shared between the UI, which changes it on key presses, and the game thread, which waits on it before every move
+#[derive(Clone)]
+pub struct Playback(Arc<Mutex<PlaybackState>>);
+
+impl Playback {
+    const MIN_DELAY: Duration = Duration::from_millis(10);
+    const MAX_DELAY: Duration = Duration::from_secs(5);
+
+    pub fn new(delay: Duration) -> Self {
+        Self(Arc::new(Mutex::new(PlaybackState { paused: false, steps: 0, delay, stopped: false })))
+    }
+
+    pub fn toggle_pause(&self) {
+        let mut state = self.0.lock().unwrap();
+        state.paused = !state.paused;
+    }
+
+    // Plays one more move while paused
+    pub fn step(&self) {
+        let mut state = self.0.lock().unwrap();
+        state.paused = true;
+        state.steps += 1;
+    }
+
+    pub fn faster(&self) {
+        let mut state = self.0.lock().unwrap();
+        state.delay = (state.delay / 2).max(Self::MIN_DELAY);
+    }
+
+    pub fn slower(&self) {
+        let mut state = self.0.lock().unwrap();
+        state.delay = (state.delay * 2).min(Self::MAX_DELAY);
+    }
+
+    pub fn stop(&self) {
+        self.0.lock().unwrap().stopped = true;
+    }
+
+    fn is_stopped(&self) -> bool {
+        self.0.lock().unwrap().stopped
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.0.lock().unwrap().paused
+    }
+
+    pub fn delay(&self) -> Duration {
+        self.0.lock().unwrap().delay
+    }
+
+    // Blocks until the next move may be played, false once stopped
+    fn wait_for_move(&self, last_move: Instant) -> bool {
+        loop {
+            {
+                let mut state = self.0.lock().unwrap();
+                if state.stopped {
+                    return false;
+                }
+                if state.paused && state.steps > 0 {
+                    state.steps -= 1;
+                    return true;
+                }
+                if !state.paused && last_move.elapsed() >= state.delay {
+                    return true;
+                }
+            }
+            thread::sleep(Duration::from_millis(5));
+        }
+    }
+}
+
+pub type GameOutcome = Option<Result<GameResult, GameError>>;
+pub type GameHandle<BotA, BotB> = JoinHandle<(Game<BotA, BotB>, GameOutcome)>;
+
+// Plays the game move by move on its own thread. The outcome is None if the playback was stopped before the end.
+pub fn spawn_game<BotA, BotB, Evaluator>(mut game: Game<BotA, BotB>, evaluate_result: Evaluator, playback: Playback) -> (Receiver<GameEvent>, GameHandle<BotA, BotB>)
+where
+BotA: Bot + 'static,
+BotB: Bot + 'static,
+Evaluator: Fn(GameState) -> GameResult + Send + 'static {
+    let (tx, rx) = channel();
+    let handle = thread::spawn(move || {
+        let mut last_move = Instant::now();
+        loop {
+            if let Some(result) = game.check_result(&evaluate_result) {
+                tx.send(GameEvent::Finished(result)).ok();
+                return (game, Some(Ok(result)));
+            }
+            if !playback.wait_for_move(last_move) {
+                return (game, None);
+            }
+
+            let round = game.round() + 1;
+            let player = game.state().current_player();
+            match game.play_move() {
+                Ok(move_played) => {
+                    tx.send(GameEvent::Moved { round, player, move_played, state: *game.state(), stats: Box::new(game.stats().clone()) }).ok();
+                }
+                // Quitting during a human turn closes its input, that is not a failure of the bot
+                Err(_) if playback.is_stopped() => return (game, None),
+                Err(err) => {
+                    tx.send(GameEvent::Failed(err.to_string())).ok();
+                    return (game, Some(Err(err)));
+                }
+            }
+            last_move = Instant::now();
+        }
+    });
+    (rx, handle)
+}
+
+// Feeds lines typed into the UI to a `HumanBot`, reading ends when the UI drops its sender
+pub struct ChannelInput {
+    lines: Receiver<String>,
+    buffer: Vec<u8>,
+}
+
+impl Read for ChannelInput {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.buffer.is_empty() {
+            match self.lines.recv() {
+                Ok(line) => self.buffer = line.into_bytes(),
+                Err(_) => return Ok(0),
+            }
+        }
+        let count = buf.len().min(self.buffer.len());
+        buf[..count].copy_from_slice(&self.buffer[..count]);
+        self.buffer.drain(..count);
+        Ok(count)
+    }
+}
+
+pub struct ChannelOutput(Sender<String>);
+
+impl Write for ChannelOutput {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.send(String::from_utf8_lossy(buf).into_owned()).map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "UI closed"))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+pub type ChannelHumanBot = HumanBot<BufReader<ChannelInput>, ChannelOutput>;
+
+// The UI side of a `ChannelHumanBot`
+pub struct Console {
+    pub input: Sender<String>,
+    pub output: Receiver<String>,
+}
+
+pub fn channel_human() -> (ChannelHumanBot, Console) {
+    let (input_tx, input_rx) = channel();
+    let (output_tx, output_rx) = channel();
+    let bot = HumanBot::new(BufReader::new(ChannelInput { lines: input_rx, buffer: vec![] }), ChannelOutput(output_tx), false).embedded();
+    (bot, Console { input: input_tx, output: output_rx })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use risk::bots::random_bot::RandomBot;
+    use risk::bots::Bot;
+    use risk::game::{self, Game};
+    use risk::game_state::{GamePhase, GameStateDuringInitialPlacement, Move};
+
+    use super::{channel_human, spawn_game, GameEvent, Playback};
+
+    #[test]
+    fn plays_until_finished() {
+        let playback = Playback::new(Duration::ZERO);
+        let (events, handle) = spawn_game(Game::new(RandomBot {}, RandomBot {}), game::evaluate_win, playback);
+        let moves = events.iter().filter(|event| matches!(event, GameEvent::Moved { .. })).count();
+        let (game, outcome) = handle.join().unwrap();
+        assert!(moves > 0);
+        assert_eq!(outcome.unwrap().unwrap(), game::evaluate_win(*game.state()));
+    }
+
+    #[test]
+    fn step_while_paused() {
+        let playback = Playback::new(Duration::ZERO);
+        playback.toggle_pause();
+        let (events, handle) = spawn_game(Game::new(RandomBot {}, RandomBot {}), game::evaluate_win, playback.clone());
+        playback.step();
+        assert!(matches!(events.recv().unwrap(), GameEvent::Moved { round: 1, .. }));
+        assert!(events.recv_timeout(Duration::from_millis(50)).is_err());
+        playback.stop();
+        assert!(handle.join().unwrap().1.is_none());
+    }
+
+    #[test]
+    fn quit_during_human_turn() {
+        let (bot, console) = channel_human();
+        let playback = Playback::new(Duration::ZERO);
+        let (_events, handle) = spawn_game(Game::new(bot, RandomBot {}), game::evaluate_win, playback.clone());
+        // Wait until the human is asked for a move
+        console.output.recv().unwrap();
+        playback.stop();
+        drop(console);
+        assert!(handle.join().unwrap().1.is_none());
+    }
+
+    #[test]
+    fn human_over_channels() {
+        let state = GameStateDuringInitialPlacement::new().place_random().start();
+        let GamePhase::Reinforce(armies) = state.phase() else { panic!("Game starts with reinforcements") };
+        let territory = state.territories_states_of_player(state.current_player())[0].territory();
+
+        let (mut bot, console) = channel_human();
+        console.input.send(format!("reinforce {}\n", territory)).unwrap();
+        assert_eq!(bot.make_move(state), Move::Reinforce { territory, armies });
+        assert!(console.output.try_iter().collect::<String>().contains("armies left"));
+    }
+}
@@ -0,0 +1,175 @@
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::symbols::Marker;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::canvas::{Canvas, Circle, Line as CanvasLine};
+use ratatui::widgets::{Block, List, ListItem, Paragraph, Row, Table};
+use ratatui::Frame;
+use strum::IntoEnumIterator;
+
+use risk::game_state::{GamePhase, Move};
+use risk::player::Player;
+use risk::territories::{Territory, MAP_COORDS, NEIGHBORS};
+
+use super::App;
+
+const MAP_WIDTH: f64 = 800.0;
+const MAP_HEIGHT: f64 = 533.0;
+
+fn player_color(player: Player) -> Color {
+    let (red, green, blue) = player.color();
+    Color::Rgb((red * 255.0) as u8, (green * 255.0) as u8, (blue * 255.0) as u8)
+}
+
+fn highlighted(app: &App, territory: Territory) -> bool {
+    match app.last_move {
+        Some((_, Move::Reinforce { territory: t, .. })) => t == territory,
+        Some((_, Move::Attack { from, to, .. })) | Some((_, Move::Fortify { from, to, .. })) => from == territory || to == territory,
+        _ => false,
+    }
+}
+
+pub fn draw(frame: &mut Frame, app: &App) {
+    let [main, bottom, status] = Layout::vertical([Constraint::Min(12), Constraint::Length(14), Constraint::Length(1)]).areas(frame.area());
+    let [map, territories] = Layout::horizontal([Constraint::Min(40), Constraint::Length(36)]).areas(main);
+    draw_map(frame, app, map);
+    draw_territories(frame, app, territories);
+
+    if app.console.is_some() {
+        let [log, stats, console] = Layout::horizontal([Constraint::Percentage(30), Constraint::Percentage(30), Constraint::Percentage(40)]).areas(bottom);
+        draw_log(frame, app, log);
+        draw_stats(frame, app, stats);
+        draw_console(frame, app, console);
+    } else {
+        let [log, stats] = Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(bottom);
+        draw_log(frame, app, log);
+        draw_stats(frame, app, stats);
+    }
+    draw_status(frame, app, status);
+}
+
+fn draw_map(frame: &mut Frame, app: &App, area: Rect) {
+    let canvas = Canvas::default()
+        .block(Block::bordered().title("Map"))
+        .marker(Marker::Braille)
+        .x_bounds([0.0, MAP_WIDTH])
+        .y_bounds([0.0, MAP_HEIGHT])
+        .paint(|ctx| {
+            for &(a, b) in NEIGHBORS {
+                let ((x1, y1), (x2, y2)) = (MAP_COORDS[a as usize], MAP_COORDS[b as usize]);
+                // Alaska - Kamchatka wraps around the edge of the map
+                if (x1 - x2).abs() < MAP_WIDTH / 2.0 {
+                    ctx.draw(&CanvasLine { x1, y1: MAP_HEIGHT - y1, x2, y2: MAP_HEIGHT - y2, color: Color::DarkGray });
+                }
+            }
+            ctx.layer();
+            for territory in Territory::iter() {
+                let (x, y) = MAP_COORDS[territory as usize];
+                let state = app.state.territory_state(territory);
+                let color = player_color(state.player());
+                ctx.draw(&Circle { x, y: MAP_HEIGHT - y, radius: 14.0, color });
+                let mut style = Style::default().fg(color).add_modifier(Modifier::BOLD);
+                if highlighted(app, territory) {
+                    style = style.add_modifier(Modifier::REVERSED);
+                }
+                ctx.print(x - 6.0, MAP_HEIGHT - y, Span::styled(format!("{}", state.armies()), style));
+            }
+        });
+    frame.render_widget(canvas, area);
+}
+
+fn draw_territories(frame: &mut Frame, app: &App, area: Rect) {
+    let rows = Territory::iter().map(|territory| {
+        let state = app.state.territory_state(territory);
+        let mut style = Style::default().fg(player_color(state.player()));
+        if highlighted(app, territory) {
+            style = style.add_modifier(Modifier::REVERSED);
+        }
+        Row::new(vec![territory.to_string(), format!("{}", state.armies())]).style(style)
+    });
+    let table = Table::new(rows, [Constraint::Min(24), Constraint::Length(6)])
+        .header(Row::new(vec!["Territory", "Armies"]).style(Style::default().add_modifier(Modifier::BOLD)))
+        .block(Block::bordered().title("Territories"));
+    frame.render_widget(table, area);
+}
+
+fn draw_log(frame: &mut Frame, app: &App, area: Rect) {
+    let visible = area.height.saturating_sub(2) as usize;
+    let items = app.log.iter().skip(app.log.len().saturating_sub(visible)).map(|line| ListItem::new(line.as_str()));
+    frame.render_widget(List::new(items).block(Block::bordered().title("Moves")), area);
+}
+
+fn draw_stats(frame: &mut Frame, app: &App, area: Rect) {
+    let mut lines = vec![];
+    for player in Player::iter() {
+        let territories = app.state.territories_states_of_player(player);
+        let armies = territories.iter().map(|t| t.state().armies() as u32).sum::<u32>();
+        let continents = app.state.continents_for_player(player);
+        let stats = &app.stats.players[player as usize];
+        lines.push(Line::styled(format!("{} - {}", player, app.name(player)), Style::default().fg(player_color(player)).add_modifier(Modifier::BOLD)));
+        lines.push(Line::raw(format!("  {} territories, {} armies, +{} per turn", territories.len(), armies, app.state.number_of_reinforcements(player))));
+        lines.push(Line::raw(format!("  attacks won {}/{}, armies lost {}", stats.attacks_won, stats.attack_moves, stats.armies_lost)));
+        lines.push(Line::raw(format!("  continents: {}", if continents.is_empty() { "-".to_string() } else { continents.iter().map(|c| format!("{:?}", c)).collect::<Vec<_>>().join(", ") })));
+    }
+    frame.render_widget(Paragraph::new(lines).block(Block::bordered().title("Players")), area);
+}
+
+fn draw_console(frame: &mut Frame, app: &App, area: Rect) {
+    let [output, input] = Layout::vertical([Constraint::Min(3), Constraint::Length(3)]).areas(area);
+    let visible = output.height.saturating_sub(2) as usize;
+    let lines = app.console_lines.iter().skip(app.console_lines.len().saturating_sub(visible)).map(|line| Line::raw(line.as_str())).collect::<Vec<_>>();
+    frame.render_widget(Paragraph::new(lines).block(Block::bordered().title("Console (type help)")), output);
+    frame.render_widget(Paragraph::new(format!("{}_", app.input)).block(Block::bordered().title("Command")), input);
+}
+
+fn draw_status(frame: &mut Frame, app: &App, area: Rect) {
+    let phase = match app.state.phase() {
+        GamePhase::Reinforce(armies) => format!("reinforce ({})", armies),
+        GamePhase::Attack => "attack".to_string(),
+        GamePhase::Fortify => "fortify".to_string(),
+    };
+    let playback = if app.playback.is_paused() { "paused".to_string() } else { format!("{} ms/move", app.playback.delay().as_millis()) };
+    let keys = if app.console.is_some() { "Ctrl-P pause  → step  ↑/↓ speed  Esc quit" } else { "space pause  n step  +/- speed  q quit" };
+    let text = match &app.status {
+        Some(status) => format!(" {} | {}", status, keys),
+        None => format!(" Round {} | {} | {} | {} | {}", app.round, app.state.current_player(), phase, playback, keys),
+    };
+    frame.render_widget(Paragraph::new(text).style(Style::default().add_modifier(Modifier::REVERSED)), area);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    use risk::game::GameResult;
+    use risk::game_state::{GameStateDuringInitialPlacement, Move};
+    use risk::game_stats::GameStats;
+    use risk::player::Player;
+    use crate::tui::runner::{GameEvent, Playback};
+    use crate::tui::App;
+
+    #[test]
+    fn draws_game() {
+        let state = GameStateDuringInitialPlacement::new().place_random().start();
+        let territory = state.territories_states_of_player(Player::A)[0].territory();
+        let mut app = App::new(["rule_based".to_string(), "random".to_string()], state, Playback::new(Duration::ZERO), None);
+        let move_played = Move::Reinforce { territory, armies: 1 };
+        let next = state.apply_move(&move_played).unwrap().random_state_by_probability();
+        app.apply(GameEvent::Moved { round: 1, player: Player::A, move_played, state: next, stats: Box::new(GameStats::new(&next)) });
+
+        let mut terminal = Terminal::new(TestBackend::new(140, 50)).unwrap();
+        terminal.draw(|frame| super::draw(frame, &app)).unwrap();
+        let screen = terminal.backend().buffer().content().iter().map(|cell| cell.symbol()).collect::<String>();
+        assert!(screen.contains("Round 1"));
+        assert!(screen.contains("rule_based"));
+        assert!(screen.contains(&territory.to_string()));
+
+        app.apply(GameEvent::Finished(GameResult::Win(Player::B)));
+        terminal.draw(|frame| super::draw(frame, &app)).unwrap();
+        let screen = terminal.backend().buffer().content().iter().map(|cell| cell.symbol()).collect::<String>();
+        assert!(screen.contains("Player B (random) won after 1 rounds"));
+    }
+}
@@ -0,0 +1,237 @@
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+use crate::game::{GameConfig, GameResult};
+use crate::game_state::{GameState, Move};
+use crate::player::Player;
+use crate::protocol::{self, BoardState, MoveRequest, Request, PROTOCOL_VERSION};
+
+use super::{Bot, BotError, MoveContext};
+
+struct Process {
+    child: Child,
+    stdin: ChildStdin,
+    lines: Receiver<String>,
+}
+
+impl Process {
+    fn spawn(command: &[String]) -> Result<Self, String> {
+        let mut child = Command::new(&command[0])
+            .args(&command[1..])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(|e| format!("Could not start '{}': {}", command.join(" "), e))?;
+        let stdin = child.stdin.take().unwrap();
+        let stdout = child.stdout.take().unwrap();
+
+        // Reading happens on its own thread so a bot that hangs runs into the timeout instead of blocking the game
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else { break };
+                if !line.trim().is_empty() && sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut process = Self { child, stdin, lines };
+        process.send(&Request::Hello { protocol: PROTOCOL_VERSION })?;
+        Ok(process)
+    }
+
+    fn send(&mut self, request: &Request) -> Result<(), String> {
+        self.stdin.write_all(request.to_line().as_bytes()).and_then(|_| self.stdin.flush()).map_err(|e| format!("Could not write to bot: {}", e))
+    }
+}
+
+impl Drop for Process {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+// Runs a bot in a child process that speaks the line protocol in PROTOCOL.md on stdin and stdout.
+// A new process is started for every game and restarted if it dies, every move request carries the full state.
+pub struct ExternalBot {
+    command: Vec<String>,
+    timeout: Duration,
+    max_restarts: u32,
+    restarts: u32,
+    process: Option<Process>,
+    // Sent again to a restarted process
+    new_game: Option<Request>,
+}
+
+impl ExternalBot {
+    pub fn new(program: &str, args: &[&str]) -> Self {
+        let command = std::iter::once(program).chain(args.iter().copied()).map(String::from).collect();
+        Self { command, timeout: Duration::from_secs(5), max_restarts: 3, restarts: 0, process: None, new_game: None }
+    }
+
+    // Splits on whitespace, e.g. "python3 bots/greedy.py"
+    pub fn from_command_line(command_line: &str) -> Result<Self, String> {
+        let words = command_line.split_whitespace().collect::<Vec<_>>();
+        let Some((program, args)) = words.split_first() else {
+            return Err("Empty command for external bot".to_string());
+        };
+        Ok(Self::new(program, args))
+    }
+
+    // Time the bot has for every single move, it forfeits when it takes longer
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    // How often a crashed process is started again before the bot forfeits
+    pub fn max_restarts(mut self, max_restarts: u32) -> Self {
+        self.max_restarts = max_restarts;
+        self
+    }
+
+    fn start(&mut self) -> Result<(), String> {
+        let mut process = Process::spawn(&self.command)?;
+        if let Some(new_game) = &self.new_game {
+            process.send(new_game)?;
+        }
+        self.process = Some(process);
+        Ok(())
+    }
+
+    // Failures show up on the next move, where they count as a crash
+    fn notify(&mut self, request: &Request) {
+        if let Some(process) = &mut self.process {
+            process.send(request).ok();
+        }
+    }
+
+    fn crashed(&mut self, message: String) -> Result<(), BotError> {
+        self.process = None;
+        if self.restarts >= self.max_restarts {
+            return Err(BotError::Crashed(message));
+        }
+        self.restarts += 1;
+        Ok(())
+    }
+}
+
+impl Bot for ExternalBot {
+    fn make_move(&mut self, game_state: GameState) -> Move {
+        self.try_make_move(game_state, &MoveContext::default()).unwrap_or_else(|err| panic!("External bot {}", err))
+    }
+
+    fn try_make_move(&mut self, game_state: GameState, context: &MoveContext) -> Result<Move, BotError> {
+        let request = Request::Move(MoveRequest::new(&game_state, context));
+        let timeout = context.time_for_move.map_or(self.timeout, |time| time.min(self.timeout));
+        loop {
+            if self.process.is_none() {
+                if let Err(message) = self.start() {
+                    self.crashed(message)?;
+                    continue;
+                }
+            }
+            let process = self.process.as_mut().unwrap();
+
+            if let Err(message) = process.send(&request) {
+                self.crashed(message)?;
+                continue;
+            }
+            match process.lines.recv_timeout(timeout) {
+                Ok(line) => return protocol::parse_move(&line).map_err(BotError::Protocol),
+                Err(RecvTimeoutError::Disconnected) => {
+                    let status = process.child.wait().map(|s| s.to_string()).unwrap_or_else(|e| e.to_string());
+                    self.crashed(format!("Bot exited ({})", status))?;
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    // The bot may still answer this request later, so it is not reused
+                    self.process = None;
+                    return Err(BotError::Timeout(timeout));
+                }
+            }
+        }
+    }
+
+    // Starts the process, so the bot can prepare before its first move
+    fn new_game(&mut self, seat: Player, config: &GameConfig) {
+        self.new_game = Some(Request::NewGame { player: seat, opponent: None, config: *config });
+        self.process = None;
+        self.restarts = 0;
+        self.start().ok();
+    }
+
+    fn observe(&mut self, player: Player, move_played: &Move, state: &GameState) {
+        self.notify(&Request::Update { player, move_played: *move_played, state: BoardState::new(state) });
+    }
+
+    fn game_over(&mut self, result: GameResult) {
+        self.notify(&Request::game_over(result, None));
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::time::Duration;
+
+    use crate::bots::random_bot::RandomBot;
+    use crate::bots::{Bot, BotError, MoveContext};
+    use crate::game::{evaluate_win, Game, GameConfig, GameError, PlayOptions};
+    use crate::game_state::GameStateDuringInitialPlacement;
+
+    use super::ExternalBot;
+
+    // Answers every move request with the first legal move, legal moves never contain nested objects
+    const FIRST_MOVE: &str = r#"s/.*"legal_moves":\[\({[^}]*}\).*/\1/"#;
+
+    // A bot that answers every move request with the sed substitution applied to it
+    fn shell(substitution: &str) -> ExternalBot {
+        shell_with("", substitution)
+    }
+
+    fn shell_with(before: &str, substitution: &str) -> ExternalBot {
+        let script = format!(r#"{} grep --line-buffered '"type":"move"' | sed -u '{}'"#, before, substitution);
+        ExternalBot::new("sh", &["-c", &script])
+    }
+
+    #[test]
+    fn plays_a_game() {
+        let config = GameConfig { max_rounds: 10, ..GameConfig::default() };
+        let mut game = Game::new(shell(FIRST_MOVE), RandomBot {}).with_config(config);
+        let result = game.play_until_end(&evaluate_win, &PlayOptions::default());
+        assert!(result.is_ok(), "{:?}", result.err());
+    }
+
+    #[test]
+    fn restarts_after_crash() {
+        let state = GameStateDuringInitialPlacement::new().place_random().start();
+        let mut bot = shell_with("head -n 2 |", FIRST_MOVE).max_restarts(1);
+        assert!(bot.try_make_move(state, &MoveContext::default()).is_ok());
+        assert!(bot.try_make_move(state, &MoveContext::default()).is_ok());
+
+        let mut bot = shell_with("head -n 2 |", FIRST_MOVE).max_restarts(0);
+        assert!(bot.try_make_move(state, &MoveContext::default()).is_ok());
+        assert!(matches!(bot.try_make_move(state, &MoveContext::default()), Err(BotError::Crashed(_))));
+    }
+
+    #[test]
+    fn timeout_and_garbage() {
+        let state = GameStateDuringInitialPlacement::new().place_random().start();
+        let timeout = Duration::from_millis(100);
+        assert_eq!(ExternalBot::new("sleep", &["5"]).timeout(timeout).try_make_move(state, &MoveContext::default()), Err(BotError::Timeout(timeout)));
+        assert!(matches!(shell("s/.*/hello/").try_make_move(state, &MoveContext::default()), Err(BotError::Protocol(_))));
+        assert!(matches!(ExternalBot::new("./does-not-exist", &[]).try_make_move(state, &MoveContext::default()), Err(BotError::Crashed(_))));
+    }
+
+    #[test]
+    fn forfeits_illegal_move() {
+        let mut game = Game::new(shell(r#"s/.*/{"type":"pass"}/"#), RandomBot {});
+        let result = game.play_until_end(&evaluate_win, &PlayOptions::default());
+        assert!(matches!(result, Err(GameError::IllegalMove { .. })));
+    }
+}
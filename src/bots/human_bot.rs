@@ -0,0 +1,338 @@
+use std::collections::VecDeque;
+use std::io::{self, BufRead, BufReader, Stdin, Stdout, Write};
+
+#[cfg(feature = "terminal-preview")]
+use crate::game_state::DrawMapOptions;
+use crate::game_state::{GamePhase, GameState, Move};
+use crate::territories::Territory;
+
+use super::{Bot, BotError, MoveContext};
+
+const HELP: &str = "Commands:
+  reinforce <territory> [armies]       r, defaults to all remaining armies
+  attack <from> <to> [armies]          a, armies move in on a capture, defaults to all but one
+  fortify <from> <to> [armies]         f, ends the turn, defaults to all but one
+  pass                                 p, ends the attack phase and the turn
+  undo                                 u, takes back the last reinforcement of this turn
+  moves                                m, lists the legal moves
+  board                                b, shows all territories
+  map                                  draws the map in the terminal
+  help                                 h
+Territories can be abbreviated, e.g. 'attack kam alaska 3'";
+
+enum Command {
+    Play(Move),
+    Undo,
+    Moves,
+    Board,
+    Map,
+    Help,
+}
+
+// Reinforcements are only collected until all armies are placed, so they can be taken back.
+// Attacks are played right away, the dice can not be undone.
+pub struct HumanBot<R: BufRead, W: Write> {
+    input: R,
+    output: W,
+    show_map: bool,
+    embedded: bool,
+    pending: Vec<Move>,
+    queued: VecDeque<Move>,
+}
+
+impl HumanBot<BufReader<Stdin>, Stdout> {
+    pub fn stdio(show_map: bool) -> Self {
+        Self::new(BufReader::new(io::stdin()), io::stdout(), show_map)
+    }
+}
+
+impl<R: BufRead, W: Write> HumanBot<R, W> {
+    pub fn new(input: R, output: W, show_map: bool) -> Self {
+        Self { input, output, show_map, embedded: false, pending: vec![], queued: VecDeque::new() }
+    }
+
+    // For front ends that show the board themselves, the bot then only prompts for moves
+    pub fn embedded(mut self) -> Self {
+        self.embedded = true;
+        self.show_map = false;
+        self
+    }
+
+    fn say(&mut self, text: &str) {
+        writeln!(self.output, "{}", text).and_then(|_| self.output.flush()).expect("Could not write to output");
+    }
+
+    fn preview(&self, game_state: GameState) -> GameState {
+        self.pending.iter().fold(game_state, |state, m| state.apply_move(m).expect("Queued move became illegal").random_state_by_probability())
+    }
+
+    fn show_board(&mut self, game_state: GameState) {
+        self.say(&game_state.to_string());
+        if self.show_map {
+            self.print_map(game_state);
+        }
+    }
+
+    #[cfg(feature = "terminal-preview")]
+    fn print_map(&mut self, game_state: GameState) {
+        let filename = std::env::temp_dir().join("risk-human-map.png");
+        if let Err(err) = game_state.draw_map(DrawMapOptions::default().filename(&filename.to_string_lossy()).should_print()) {
+            self.say(&err.to_string());
+        }
+    }
+
+    #[cfg(not(feature = "terminal-preview"))]
+    fn print_map(&mut self, _game_state: GameState) {
+        self.say("Built without the terminal-preview feature, there is no map");
+    }
+
+    fn prompt(&mut self, game_state: GameState) {
+        let phase = match game_state.phase() {
+            GamePhase::Reinforce(armies) => format!("reinforce, {} armies left", armies),
+            GamePhase::Attack => "attack".to_string(),
+            GamePhase::Fortify => "fortify".to_string(),
+        };
+        write!(self.output, "{} ({})> ", game_state.current_player(), phase).and_then(|_| self.output.flush()).expect("Could not write to output");
+    }
+
+    // None once the input is closed
+    fn read_command(&mut self, game_state: GameState) -> Option<Result<Command, String>> {
+        let mut line = String::new();
+        if self.input.read_line(&mut line).expect("Could not read input") == 0 {
+            return None;
+        }
+        Some(parse_command(&line, &game_state))
+    }
+}
+
+fn parse_armies(word: Option<&&str>, default: u8) -> Result<u8, String> {
+    match word {
+        Some(word) => word.parse().map_err(|_| format!("Expected a number of armies, found '{}'", word)),
+        None => Ok(default),
+    }
+}
+
+fn parse_command(line: &str, game_state: &GameState) -> Result<Command, String> {
+    let words = line.split_whitespace().collect::<Vec<_>>();
+    let Some((command, arguments)) = words.split_first() else {
+        return Err("Type 'help' for a list of commands".to_string());
+    };
+
+    // Territory names can contain spaces, so the last word is only taken as armies if it is a number
+    let (names, armies) = match arguments.last() {
+        Some(last) if last.parse::<u8>().is_ok() => (&arguments[..arguments.len() - 1], arguments.last()),
+        _ => (arguments, None),
+    };
+    let territory = |name: &str| name.parse::<Territory>();
+    let all_but_one = |from: Territory| game_state.territory_state(from).armies().saturating_sub(1);
+
+    match command.to_lowercase().as_str() {
+        "reinforce" | "r" => {
+            let territory = territory(&names.join(" "))?;
+            let remaining = match game_state.phase() {
+                GamePhase::Reinforce(remaining) => remaining,
+                _ => 0,
+            };
+            Ok(Command::Play(Move::Reinforce { territory, armies: parse_armies(armies, remaining)? }))
+        }
+        "attack" | "a" | "fortify" | "f" => {
+            let (from, to) = split_territories(names)?;
+            let armies = parse_armies(armies, all_but_one(from))?;
+            if command.starts_with('a') {
+                Ok(Command::Play(Move::Attack { from, to, attacking: armies }))
+            } else {
+                Ok(Command::Play(Move::Fortify { from, to, armies }))
+            }
+        }
+        "pass" | "p" | "end" => Ok(Command::Play(Move::Pass)),
+        "undo" | "u" => Ok(Command::Undo),
+        "moves" | "m" => Ok(Command::Moves),
+        "board" | "b" => Ok(Command::Board),
+        "map" => Ok(Command::Map),
+        "help" | "h" | "?" => Ok(Command::Help),
+        _ => Err(format!("Unknown command '{}', type 'help' for a list of commands", command)),
+    }
+}
+
+// Tries every split of the words into two territory names, e.g. "north africa egypt"
+fn split_territories(words: &[&str]) -> Result<(Territory, Territory), String> {
+    let mut last_error = format!("Expected two territories, found '{}'", words.join(" "));
+    for split in 1..words.len() {
+        match (words[..split].join(" ").parse::<Territory>(), words[split..].join(" ").parse::<Territory>()) {
+            (Ok(from), Ok(to)) => return Ok((from, to)),
+            (Err(err), _) | (_, Err(err)) => last_error = err,
+        }
+    }
+    Err(last_error)
+}
+
+// Collapses the legal moves into one line per territory pair with the range of armies
+fn describe_moves(game_state: &GameState) -> String {
+    let mut reinforce: Vec<(Territory, u8)> = vec![];
+    let mut attack: Vec<(Territory, Territory, u8)> = vec![];
+    let mut fortify: Vec<(Territory, Territory, u8)> = vec![];
+    let mut can_pass = false;
+    for m in game_state.legal_moves() {
+        match m {
+            Move::Reinforce { territory, armies } => match reinforce.iter_mut().find(|(t, _)| *t == territory) {
+                Some(entry) => entry.1 = entry.1.max(armies),
+                None => reinforce.push((territory, armies)),
+            },
+            Move::Attack { from, to, attacking } => match attack.iter_mut().find(|(f, t, _)| *f == from && *t == to) {
+                Some(entry) => entry.2 = entry.2.max(attacking),
+                None => attack.push((from, to, attacking)),
+            },
+            Move::Fortify { from, to, armies } => match fortify.iter_mut().find(|(f, t, _)| *f == from && *t == to) {
+                Some(entry) => entry.2 = entry.2.max(armies),
+                None => fortify.push((from, to, armies)),
+            },
+            Move::Pass => can_pass = true,
+        }
+    }
+
+    let mut lines = vec![];
+    if !reinforce.is_empty() {
+        lines.push("Reinforce:".to_string());
+        lines.extend(reinforce.iter().map(|(territory, armies)| format!("  {} (1-{})", territory, armies)));
+    }
+    if !attack.is_empty() {
+        lines.push("Attack:".to_string());
+        lines.extend(attack.iter().map(|(from, to, armies)| format!("  {} -> {} (1-{} dice, {} defending)", from, to, armies, game_state.territory_state(*to).armies())));
+    }
+    if !fortify.is_empty() {
+        lines.push("Fortify:".to_string());
+        lines.extend(fortify.iter().map(|(from, to, armies)| format!("  {} -> {} (1-{})", from, to, armies)));
+    }
+    if can_pass {
+        lines.push("Pass".to_string());
+    }
+    lines.join("\n")
+}
+
+impl<R: BufRead + Send, W: Write + Send> Bot for HumanBot<R, W> {
+    fn make_move(&mut self, game_state: GameState) -> Move {
+        self.try_make_move(game_state, &MoveContext::default()).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    // Closing the input resigns, the game counts that as a forfeit
+    fn try_make_move(&mut self, game_state: GameState, _context: &MoveContext) -> Result<Move, BotError> {
+        if let Some(queued) = self.queued.pop_front() {
+            return Ok(queued);
+        }
+
+        self.say(&format!("\n{}'s turn", game_state.current_player()));
+        if !self.embedded {
+            self.show_board(game_state);
+        }
+        loop {
+            let preview = self.preview(game_state);
+            self.prompt(preview);
+            let command = match self.read_command(preview) {
+                Some(Ok(command)) => command,
+                Some(Err(err)) => {
+                    self.say(&err);
+                    continue;
+                }
+                None => return Err(BotError::Crashed(format!("{} resigned, input closed", game_state.current_player()))),
+            };
+
+            match command {
+                Command::Play(m) => {
+                    let next = match preview.apply_move(&m) {
+                        Ok(result) => result,
+                        Err(err) => {
+                            self.say(&format!("Illegal move {:?}: {:?}", m, err));
+                            continue;
+                        }
+                    };
+                    if !matches!(m, Move::Reinforce { .. }) {
+                        return Ok(m);
+                    }
+
+                    self.pending.push(m);
+                    if !matches!(next.random_state_by_probability().phase(), GamePhase::Reinforce(_)) {
+                        self.queued.extend(self.pending.drain(..));
+                        return Ok(self.queued.pop_front().unwrap());
+                    }
+                }
+                Command::Undo => match self.pending.pop() {
+                    Some(m) => self.say(&format!("Took back {:?}", m)),
+                    None => self.say("Nothing to undo, attacks and fortifications are final"),
+                },
+                Command::Moves => {
+                    let moves = describe_moves(&preview);
+                    self.say(&moves);
+                }
+                Command::Board => self.show_board(preview),
+                Command::Map if self.embedded => self.say("The map is already shown"),
+                Command::Map => {
+                    let show_map = self.show_map;
+                    self.show_map = true;
+                    self.show_board(preview);
+                    self.show_map = show_map;
+                }
+                Command::Help => self.say(HELP),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use strum::EnumCount;
+
+    use crate::bots::{Bot, BotError, MoveContext};
+    use crate::game_state::{GamePhase, GameState, GameStateDuringInitialPlacement, Move, TerritoryState};
+    use crate::player::Player;
+    use crate::territories::Territory;
+
+    use super::HumanBot;
+
+    #[test]
+    fn reinforce_with_undo() {
+        let state = GameStateDuringInitialPlacement::new().place_random().start();
+        let GamePhase::Reinforce(armies) = state.phase() else { panic!("Game starts with reinforcements") };
+        let own = state.territories_states_of_player(state.current_player());
+        let (first, second) = (own[0].territory(), own[1].territory());
+
+        let input = format!("reinforce {} 1\nundo\nr atlantis\nmoves\nreinforce {} 1\nr {:?}\n", first, second, first);
+        let mut output = vec![];
+        let mut bot = HumanBot::new(Cursor::new(input), &mut output, false);
+
+        assert_eq!(bot.make_move(state), Move::Reinforce { territory: second, armies: 1 });
+        let state = state.apply_move(&Move::Reinforce { territory: second, armies: 1 }).unwrap().random_state_by_probability();
+        assert_eq!(bot.make_move(state), Move::Reinforce { territory: first, armies: armies - 1 });
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("Took back"));
+        assert!(output.contains("Unknown territory 'atlantis'"));
+        assert!(output.contains("Reinforce:"));
+    }
+
+    #[test]
+    fn attack_by_abbreviation() {
+        let mut territories = [TerritoryState::new(Player::B, 3); Territory::COUNT];
+        territories[Territory::Kamchatka as usize] = TerritoryState::new(Player::A, 5);
+        territories[Territory::WesternUnitedStates as usize] = TerritoryState::new(Player::A, 4);
+        territories[Territory::EasternUnitedStates as usize] = TerritoryState::new(Player::A, 2);
+
+        // A prefix and a full name, then initials with the armies left out
+        let input = "a kam alaska 2\nf wus eus\n";
+        let mut output = vec![];
+        let mut bot = HumanBot::new(Cursor::new(input), &mut output, false);
+        let state = GameState::from_parts(Player::A, GamePhase::Attack, territories);
+        assert_eq!(bot.make_move(state), Move::Attack { from: Territory::Kamchatka, to: Territory::Alaska, attacking: 2 });
+        let state = GameState::from_parts(Player::A, GamePhase::Fortify, territories);
+        assert_eq!(bot.make_move(state), Move::Fortify { from: Territory::WesternUnitedStates, to: Territory::EasternUnitedStates, armies: 3 });
+    }
+
+    #[test]
+    fn closed_input_resigns() {
+        let state = GameStateDuringInitialPlacement::new().place_random().start();
+        let mut output = vec![];
+        let mut bot = HumanBot::new(Cursor::new("help\n"), &mut output, false);
+        assert!(matches!(bot.try_make_move(state, &MoveContext::default()), Err(BotError::Crashed(message)) if message.contains("resigned")));
+    }
+}
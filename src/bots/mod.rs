@@ -1,9 +1,95 @@
+use std::fmt;
+use std::time::Duration;
+
+use crate::game::{GameConfig, GameResult};
 use crate::game_state::{GameState, Move};
+use crate::player::Player;
+
+// Weights of the neural and rule based bots and the genomes of the evolver
+pub type Float = f32;
+
+// Failures of a bot that can not answer, the game counts them as a forfeit
+#[derive(Debug, Clone, PartialEq)]
+pub enum BotError {
+    Timeout(Duration),
+    Crashed(String),
+    Protocol(String),
+}
+
+impl fmt::Display for BotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BotError::Timeout(timeout) => write!(f, "no move within {:?}", timeout),
+            BotError::Crashed(message) => write!(f, "crashed: {}", message),
+            BotError::Protocol(message) => write!(f, "protocol error: {}", message),
+        }
+    }
+}
+
+// Passed to every move, all None when the game has no time control
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MoveContext {
+    // Time until the move counts as timed out, the smaller of the move limit and the clock
+    pub time_for_move: Option<Duration>,
+    // Time left on the clock of the player for the rest of the game, without the increment of this move
+    pub clock: Option<Duration>,
+}
 
 pub trait Bot: Send {
     fn make_move(&mut self, game_state: GameState) -> Move;
+
+    // Bots that can fail without it being a bug, like external processes, or that manage their thinking time
+    // override this instead of `make_move`
+    fn try_make_move(&mut self, game_state: GameState, _context: &MoveContext) -> Result<Move, BotError> {
+        Ok(self.make_move(game_state))
+    }
+
+    // Called before the first move of every game with the seat of the bot
+    fn new_game(&mut self, _seat: Player, _config: &GameConfig) {}
+
+    // Called for the moves of both players, `state` is the state after the move including the outcome of the dice
+    fn observe(&mut self, _player: Player, _move_played: &Move, _state: &GameState) {}
+
+    // Also called when a player forfeits
+    fn game_over(&mut self, _result: GameResult) {}
+}
+
+impl<B: Bot + ?Sized> Bot for Box<B> {
+    fn make_move(&mut self, game_state: GameState) -> Move {
+        (**self).make_move(game_state)
+    }
+
+    fn try_make_move(&mut self, game_state: GameState, context: &MoveContext) -> Result<Move, BotError> {
+        (**self).try_make_move(game_state, context)
+    }
+
+    fn new_game(&mut self, seat: Player, config: &GameConfig) {
+        (**self).new_game(seat, config)
+    }
+
+    fn observe(&mut self, player: Player, move_played: &Move, state: &GameState) {
+        (**self).observe(player, move_played, state)
+    }
+
+    fn game_over(&mut self, result: GameResult) {
+        (**self).game_over(result)
+    }
 }
 
 pub mod random_bot;
 pub mod rule_based_bot;
+pub mod strategic_bot;
+#[cfg(feature = "neural")]
 pub mod neural_bot;
+pub mod human_bot;
+pub mod external_bot;
+pub mod registry;
+
+pub use external_bot::ExternalBot;
+pub use human_bot::HumanBot;
+#[cfg(feature = "neural")]
+pub use neural_bot::NeuralBot;
+pub use random_bot::RandomBot;
+pub use registry::BotRegistry;
+pub use rule_based_bot::{RuleBasedBot, RuleWeights};
+pub use strategic_bot::StrategicBot;
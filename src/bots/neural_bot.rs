@@ -1,3 +1,7 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
 use rand_distr::{Normal, Distribution};
 use rand::thread_rng;
 use rand::Rng;
@@ -7,11 +11,10 @@ use ndarray::{Array, Array1, Array2};
 use ndarray_rand::RandomExt;
 use ndarray_rand::rand_distr::Uniform;
 
-use crate::bots::Bot;
+use crate::bots::{Bot, Float};
 use crate::game_state::{GamePhase, GameState, Move, NamedTerritoryState};
 use crate::territories::{Territory, NEIGHBORS};
 
-pub type Float = f32;
 struct Layer {
     biases: Array1<Float>,
     weights: Array2<Float>,
@@ -61,6 +64,8 @@ impl NeuralNetwork {
         Array::from_vec(initialization_vector)
     }
 
+    // The counterpart of `load`, nothing saves a network from its layers at the moment
+    #[allow(dead_code)]
     fn export(&self) -> Vec<Float> {
         let mut data = Vec::new();
         for layer in &self.layers {
@@ -127,6 +132,24 @@ impl NeuralBot {
         NeuralNetwork::generate_initialization_vector(&Self::ARCHITECTURE)
     }
 
+    pub fn from_file<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Ok(Self::from_weights_and_biases(&Self::load_weights_and_biases(path)?))
+    }
+
+    // Genomes are stored as little endian floats without any header
+    pub fn load_weights_and_biases<P: AsRef<Path>>(path: P) -> io::Result<Array1<Float>> {
+        let bytes = fs::read(path)?;
+        let size = std::mem::size_of::<Float>();
+        if bytes.len() != Self::LENGTH * size {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, format!("Expected {} weights, found {} bytes", Self::LENGTH, bytes.len())));
+        }
+        Ok(Array::from_iter(bytes.chunks_exact(size).map(|chunk| Float::from_le_bytes(chunk.try_into().unwrap()))))
+    }
+
+    pub fn save_weights_and_biases<P: AsRef<Path>>(data: &[Float], path: P) -> io::Result<()> {
+        fs::write(path, data.iter().flat_map(|value| value.to_le_bytes()).collect::<Vec<_>>())
+    }
+
     fn random_move(&self, game_state: GameState) -> Move {
         let moves = game_state.legal_moves();
         if moves.is_empty() {
@@ -0,0 +1,222 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+#[cfg(feature = "neural")]
+use ndarray::Array1;
+
+use crate::tournament::BotFactory;
+
+use super::external_bot::ExternalBot;
+use super::human_bot::HumanBot;
+#[cfg(feature = "neural")]
+use super::neural_bot::NeuralBot;
+use super::random_bot::RandomBot;
+use super::rule_based_bot::{RuleBasedBot, RuleWeights};
+use super::strategic_bot::StrategicBot;
+use super::Bot;
+
+// A bot name with an optional argument after the first colon, e.g. `neural:weights/island_0.bin` or
+// `mcts:iters=2000,exploration=1.4`. What the argument means is up to the bot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BotSpec {
+    pub name: String,
+    pub argument: Option<String>,
+}
+
+impl BotSpec {
+    // Reads the argument as comma separated key=value pairs
+    pub fn params(&self) -> Result<BTreeMap<String, String>, String> {
+        let mut params = BTreeMap::new();
+        for pair in self.argument.as_deref().unwrap_or("").split(',').filter(|p| !p.trim().is_empty()) {
+            let (key, value) = pair.split_once('=').ok_or_else(|| format!("Expected key=value in '{}', found '{}'", self, pair))?;
+            params.insert(key.trim().to_string(), value.trim().to_string());
+        }
+        Ok(params)
+    }
+
+    // Parses one parameter, missing ones are None
+    pub fn param<T: FromStr>(&self, key: &str) -> Result<Option<T>, String> {
+        match self.params()?.get(key) {
+            Some(value) => value.parse().map(Some).map_err(|_| format!("Invalid value '{}' for {} in '{}'", value, key, self)),
+            None => Ok(None),
+        }
+    }
+}
+
+impl FromStr for BotSpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, argument) = match s.split_once(':') {
+            Some((name, argument)) => (name, Some(argument.to_string())),
+            None => (s, None),
+        };
+        let name = name.trim().to_lowercase().replace('-', "_");
+        if name.is_empty() {
+            return Err(format!("Missing bot name in '{}'", s));
+        }
+        Ok(Self { name, argument })
+    }
+}
+
+impl fmt::Display for BotSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.argument {
+            Some(argument) => write!(f, "{}:{}", self.name, argument),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
+
+type Constructor = Box<dyn Fn(&BotSpec) -> Result<BotFactory, String> + Send + Sync>;
+
+struct Entry {
+    names: Vec<String>,
+    usage: String,
+    constructor: Constructor,
+}
+
+// Maps bot specs to factories, so the CLI, config files and tournaments name bots the same way.
+// Arguments are checked and files are loaded once when the factory is created, not for every game.
+pub struct BotRegistry {
+    entries: Vec<Entry>,
+}
+
+impl BotRegistry {
+    pub fn empty() -> Self {
+        Self { entries: vec![] }
+    }
+
+    // `names` are the name and its aliases, `usage` is shown in errors, e.g. "neural[:<weights file>]".
+    // Registering the same names again replaces the bot.
+    pub fn register<C>(mut self, names: &[&str], usage: &str, constructor: C) -> Self
+    where
+    C: Fn(&BotSpec) -> Result<BotFactory, String> + Send + Sync + 'static {
+        let names = names.iter().map(|n| n.to_string()).collect();
+        self.entries.retain(|e| e.names != names);
+        self.entries.push(Entry { names, usage: usage.to_string(), constructor: Box::new(constructor) });
+        self
+    }
+
+    pub fn usage(&self) -> Vec<&str> {
+        self.entries.iter().map(|e| e.usage.as_str()).collect()
+    }
+
+    pub fn factory(&self, spec: &str) -> Result<BotFactory, String> {
+        let parsed = spec.parse::<BotSpec>()?;
+        let entry = self.entries.iter().find(|e| e.names.contains(&parsed.name))
+            .ok_or_else(|| format!("Unknown bot '{}', expected one of {}", spec, self.usage().join(", ")))?;
+        (entry.constructor)(&parsed)
+    }
+
+    pub fn create(&self, spec: &str) -> Result<Box<dyn Bot>, String> {
+        Ok(self.factory(spec)?())
+    }
+}
+
+fn no_argument(spec: &BotSpec) -> Result<(), String> {
+    match &spec.argument {
+        Some(argument) => Err(format!("Bot '{}' takes no argument, found '{}'", spec.name, argument)),
+        None => Ok(()),
+    }
+}
+
+// The bots of this crate
+impl Default for BotRegistry {
+    fn default() -> Self {
+        let registry = Self::empty()
+            .register(&["random"], "random", |spec| {
+                no_argument(spec)?;
+                Ok(Box::new(|| Box::new(RandomBot {}) as Box<dyn Bot>))
+            })
+            // Weights from a TOML file written by `train`, or single weights like `rule:attack_min_ratio=1.5`
+            .register(&["rule_based", "rule"], "rule_based[:<weights file>|<weight>=<value>,...]", |spec| {
+                let weights = match &spec.argument {
+                    Some(argument) if argument.contains('=') => {
+                        let mut weights = toml::Table::try_from(RuleWeights::default()).map_err(|e| e.to_string())?;
+                        for (key, value) in spec.params()? {
+                            let value = value.parse::<f64>().map_err(|_| format!("Invalid value '{}' for {} in '{}'", value, key, spec))?;
+                            weights.insert(key, toml::Value::Float(value));
+                        }
+                        weights.try_into::<RuleWeights>().map_err(|e| format!("Invalid weights in '{}': {}", spec, e))?
+                    }
+                    Some(path) => RuleWeights::load(path)?,
+                    None => RuleWeights::default(),
+                };
+                Ok(Box::new(move || Box::new(RuleBasedBot::new(weights)) as Box<dyn Bot>))
+            })
+            .register(&["strategic"], "strategic", |spec| {
+                no_argument(spec)?;
+                Ok(Box::new(|| Box::new(StrategicBot) as Box<dyn Bot>))
+            });
+        // Random weights without an argument, otherwise a genome saved by `train`
+        #[cfg(feature = "neural")]
+        let registry = registry.register(&["neural"], "neural[:<weights file>]", |spec| match &spec.argument {
+            Some(path) => {
+                let weights: Array1<_> = NeuralBot::load_weights_and_biases(path).map_err(|e| format!("Could not load weights from {}: {}", path, e))?;
+                Ok(Box::new(move || Box::new(NeuralBot::from_weights_and_biases(&weights)) as Box<dyn Bot>))
+            }
+            None => Ok(Box::new(|| Box::new(NeuralBot::default()) as Box<dyn Bot>)),
+        });
+        registry
+            // Plays on the terminal, `human:map` also prints the map after every turn
+            .register(&["human"], "human[:map]", |spec| match spec.argument.as_deref() {
+                Some("map") if !cfg!(feature = "terminal-preview") => Err("human:map needs the terminal-preview feature".to_string()),
+                Some("map") => Ok(Box::new(|| Box::new(HumanBot::stdio(true)) as Box<dyn Bot>)),
+                None => Ok(Box::new(|| Box::new(HumanBot::stdio(false)) as Box<dyn Bot>)),
+                Some(argument) => Err(format!("Unknown option '{}' for human, expected map", argument)),
+            })
+            // Starts the command for every game, see PROTOCOL.md
+            .register(&["external"], "external:<command>", |spec| {
+                let command = spec.argument.clone().unwrap_or_default();
+                ExternalBot::from_command_line(&command)?;
+                Ok(Box::new(move || Box::new(ExternalBot::from_command_line(&command).unwrap()) as Box<dyn Bot>))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bots::random_bot::RandomBot;
+    use crate::bots::Bot;
+
+    use super::{BotRegistry, BotSpec};
+
+    #[test]
+    fn parse_specs() {
+        let spec: BotSpec = "MCTS:iters=2000, exploration = 1.4".parse().unwrap();
+        assert_eq!(spec.name, "mcts");
+        assert_eq!(spec.param::<u32>("iters"), Ok(Some(2000)));
+        assert_eq!(spec.param::<f64>("exploration"), Ok(Some(1.4)));
+        assert_eq!(spec.param::<u32>("depth"), Ok(None));
+        assert!(spec.param::<u32>("exploration").is_err());
+        assert_eq!(spec.to_string(), "mcts:iters=2000, exploration = 1.4");
+
+        let spec: BotSpec = "external:python3 bot.py --fast".parse().unwrap();
+        assert_eq!((spec.name.as_str(), spec.argument.as_deref()), ("external", Some("python3 bot.py --fast")));
+        assert!(spec.params().is_err());
+        assert!(":x".parse::<BotSpec>().is_err());
+    }
+
+    #[test]
+    fn builtin_and_custom_bots() {
+        let registry = BotRegistry::default();
+        for spec in ["random", "rule", "rule-based", "rule_based:attack_share=0.5", "strategic", "human", "external:python3 bot.py"] {
+            assert!(registry.factory(spec).is_ok(), "{}", spec);
+        }
+        assert_eq!(registry.factory("neural").is_ok(), cfg!(feature = "neural"));
+        assert_eq!(registry.factory("human:map").is_ok(), cfg!(feature = "terminal-preview"));
+        for spec in ["mcts", "random:fast", "human:tui", "external:", "neural:does/not/exist.bin", "rule:attack_share=x", "rule:aggression=2"] {
+            assert!(registry.factory(spec).is_err(), "{}", spec);
+        }
+
+        let registry = registry.register(&["mcts"], "mcts[:iters=<n>]", |spec| {
+            let iters = spec.param::<u32>("iters")?.unwrap_or(1000);
+            assert_eq!(iters, 2000);
+            Ok(Box::new(|| Box::new(RandomBot {}) as Box<dyn Bot>))
+        });
+        assert!(registry.create("mcts:iters=2000").is_ok());
+        assert!(registry.usage().contains(&"mcts[:iters=<n>]"));
+    }
+}
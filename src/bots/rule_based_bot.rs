@@ -1,13 +1,91 @@
+use std::fs;
+use std::path::Path;
+
 use rand::Rng;
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 
 use crate::game_state::{GamePhase, GameState, Move, NamedTerritoryState};
 
+use super::Float;
 use super::Bot;
 
-pub struct RuleBasedBot;
+// The defaults are the values the bot was written with
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RuleWeights {
+    // Reinforcement score of a territory is reinforce_weakness / armies, plus for every enemy neighbor
+    // reinforce_enemy_neighbor + reinforce_threat * enemy armies / own armies. The quotients are truncated to whole
+    // numbers like the bot always did, ties go to the last territory.
+    pub reinforce_weakness: Float,
+    pub reinforce_enemy_neighbor: Float,
+    pub reinforce_threat: Float,
+    // Only territories with at least this many armies attack
+    pub attack_min_armies: Float,
+    // Attacks with a lower ratio of own to enemy armies, truncated as well, are not made and the turn is passed
+    pub attack_min_ratio: Float,
+    // Share of the armies that can attack, all but one, that is sent
+    pub attack_share: Float,
+}
+
+impl Default for RuleWeights {
+    fn default() -> Self {
+        Self { reinforce_weakness: 10.0, reinforce_enemy_neighbor: 1.0, reinforce_threat: 1.0, attack_min_armies: 3.0, attack_min_ratio: 2.0, attack_share: 1.0 }
+    }
+}
+
+impl RuleWeights {
+    pub const LENGTH: usize = 6;
+
+    // The genome holds every weight as a multiple of its default, so one mutation range fits all of them
+    pub fn from_genome(genome: &[Float]) -> Self {
+        let default = Self::default().to_array();
+        let mut weights = [0.0; Self::LENGTH];
+        for idx in 0..Self::LENGTH {
+            weights[idx] = genome[idx].max(0.0) * default[idx];
+        }
+        Self::from_array(weights)
+    }
+
+    pub fn to_genome(self) -> [Float; Self::LENGTH] {
+        let default = Self::default().to_array();
+        let mut genome = self.to_array();
+        for idx in 0..Self::LENGTH {
+            genome[idx] /= default[idx];
+        }
+        genome
+    }
+
+    pub fn to_array(self) -> [Float; Self::LENGTH] {
+        [self.reinforce_weakness, self.reinforce_enemy_neighbor, self.reinforce_threat, self.attack_min_armies, self.attack_min_ratio, self.attack_share]
+    }
+
+    pub fn from_array(weights: [Float; Self::LENGTH]) -> Self {
+        let [reinforce_weakness, reinforce_enemy_neighbor, reinforce_threat, attack_min_armies, attack_min_ratio, attack_share] = weights;
+        Self { reinforce_weakness, reinforce_enemy_neighbor, reinforce_threat, attack_min_armies, attack_min_ratio, attack_share }
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let content = fs::read_to_string(&path).map_err(|e| format!("Could not read {}: {}", path.as_ref().display(), e))?;
+        toml::from_str(&content).map_err(|e| format!("Invalid weights {}: {}", path.as_ref().display(), e))
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), String> {
+        let content = toml::to_string(self).map_err(|e| e.to_string())?;
+        fs::write(&path, content).map_err(|e| format!("Could not write {}: {}", path.as_ref().display(), e))
+    }
+}
+
+#[derive(Default)]
+pub struct RuleBasedBot {
+    weights: RuleWeights,
+}
 
 impl RuleBasedBot {
+    pub fn new(weights: RuleWeights) -> Self {
+        Self { weights }
+    }
+
     fn random_move(&self, game_state: GameState) -> Move {
         let moves = game_state.legal_moves();
         if moves.is_empty() {
@@ -20,18 +98,20 @@ impl RuleBasedBot {
 
 impl Bot for RuleBasedBot {
     fn make_move(&mut self, game_state: GameState) -> Move {
+        let weights = &self.weights;
         match game_state.phase() {
             GamePhase::Reinforce(_) => {
                 let my_territories = game_state.territories_states_of_player(game_state.current_player());
                 let reinforceable_territories: Vec<_> = my_territories.iter().filter(|t| t.state().armies() < u8::MAX).collect();
-                let mut scores = vec![0u32; reinforceable_territories.len()];
+                let mut scores = vec![0.0 as Float; reinforceable_territories.len()];
                 for i in 0..reinforceable_territories.len() {
                     let territory = &reinforceable_territories[i];
-                    scores[i] += (1.0 / territory.state().armies() as f64 * 10.0) as u32;
+                    let armies = territory.state().armies() as Float;
+                    scores[i] += (weights.reinforce_weakness / armies).trunc();
                     for neighbor in territory.territory().neighbors() {
                         let neighbor_territory = game_state.territory_state(neighbor);
                         if neighbor_territory.player() != game_state.current_player() {
-                            scores[i] += 1 + ((neighbor_territory.armies() as f64 / territory.state().armies() as f64)) as u32;
+                            scores[i] += weights.reinforce_enemy_neighbor + (weights.reinforce_threat * neighbor_territory.armies() as Float / armies).trunc();
                         }
                     }
                 }
@@ -40,7 +120,8 @@ impl Bot for RuleBasedBot {
                     return self.random_move(game_state);
                 }
 
-                Move::Reinforce { territory: reinforceable_territories[scores.iter().position_max().unwrap()].territory(), armies: 1 }
+                let best = scores.iter().position_max_by(|a, b| a.total_cmp(b)).unwrap();
+                Move::Reinforce { territory: reinforceable_territories[best].territory(), armies: 1 }
             }
             GamePhase::Attack => {
                 let my_territories = game_state.territories_states_of_player(game_state.current_player());
@@ -48,7 +129,7 @@ impl Bot for RuleBasedBot {
                 for territory in my_territories {
                     for neighbor in territory.territory().neighbors() {
                         let neighbor_territory_state = game_state.territory_state(neighbor);
-                        if neighbor_territory_state.player() != game_state.current_player() && territory.state().armies() > 2 {
+                        if neighbor_territory_state.player() != game_state.current_player() && territory.state().armies() >= 2 && territory.state().armies() as Float >= weights.attack_min_armies {
                             possible_attacks.push((territory, NamedTerritoryState::new(neighbor, neighbor_territory_state)));
                         }
                     }
@@ -56,17 +137,15 @@ impl Bot for RuleBasedBot {
                 if possible_attacks.is_empty() {
                     return Move::Pass;
                 }
-                let mut scores = vec![0u32; possible_attacks.len()];
-                for (idx, (our_territory, enemy_territory)) in possible_attacks.iter().enumerate() {
-                    scores[idx] += (our_territory.state().armies() as f64 / enemy_territory.state().armies() as f64) as u32
-
-                }
-                let best_score_idx = scores.iter().position_max().unwrap();
-                if scores[best_score_idx] < 2 {
+                let scores = possible_attacks.iter().map(|(our_territory, enemy_territory)| (our_territory.state().armies() as Float / enemy_territory.state().armies() as Float).trunc()).collect::<Vec<_>>();
+                let best_score_idx = scores.iter().position_max_by(|a, b| a.total_cmp(b)).unwrap();
+                if scores[best_score_idx] < weights.attack_min_ratio {
                     return Move::Pass;
                 }
                 let best_attack = possible_attacks[best_score_idx];
-                Move::Attack { from: best_attack.0.territory(), to: best_attack.1.territory(), attacking: best_attack.0.state().armies() - 1 }
+                let available = best_attack.0.state().armies() - 1;
+                let attacking = (available as Float * weights.attack_share).round().clamp(1.0, available as Float) as u8;
+                Move::Attack { from: best_attack.0.territory(), to: best_attack.1.territory(), attacking }
             }
             _ => {
                 self.random_move(game_state)
@@ -75,8 +154,62 @@ impl Bot for RuleBasedBot {
     }
 }
 
-impl Default for RuleBasedBot {
-    fn default() -> Self {
-        Self
+#[cfg(test)]
+mod tests {
+    use strum::EnumCount;
+
+    use crate::bots::random_bot::RandomBot;
+    use crate::bots::Bot;
+    use crate::arena;
+    use crate::game;
+    use crate::game_state::{GamePhase, GameState, Move, TerritoryState};
+    use crate::player::Player;
+    use crate::territories::Territory;
+
+    use super::{RuleBasedBot, RuleWeights};
+
+    #[test]
+    fn weights_roundtrip() {
+        let weights = RuleWeights { attack_min_ratio: 1.5, ..RuleWeights::default() };
+        assert_eq!(RuleWeights::from_genome(&weights.to_genome()), weights);
+        assert_eq!(RuleWeights::default().to_genome(), [1.0; RuleWeights::LENGTH]);
+
+        let path = std::env::temp_dir().join("risk-rule-weights.toml");
+        weights.save(&path).unwrap();
+        assert_eq!(RuleWeights::load(&path).unwrap(), weights);
+        assert_eq!(toml::from_str::<RuleWeights>("attack_share = 0.5").unwrap().attack_min_armies, 3.0);
+    }
+
+    #[test]
+    fn never_attacking_bot_does_not_win() {
+        let passive = RuleWeights { attack_min_ratio: 1000.0, ..RuleWeights::default() };
+        let result = arena::play_games(2, game::evaluate_win, move || RuleBasedBot::new(passive), || RandomBot {}).unwrap();
+        assert_eq!(result.wins(Player::A), 0);
+    }
+
+    // Scores are truncated before they are compared, the last of the tied candidates is picked
+    #[test]
+    fn default_choices() {
+        let board = |alaska: u8, argentina: u8| {
+            let mut territories = [TerritoryState::new(Player::B, 10); Territory::COUNT];
+            territories[Territory::Alaska as usize] = TerritoryState::new(Player::A, alaska);
+            territories[Territory::Argentina as usize] = TerritoryState::new(Player::A, argentina);
+            territories
+        };
+        let mut bot = RuleBasedBot::default();
+
+        // 2.5 + 3 * 3.5 for Alaska and 3.33 + 2 * 4.33 for Argentina both count as 11
+        let state = GameState::from_parts(Player::A, GamePhase::Reinforce(3), board(4, 3));
+        assert_eq!(bot.make_move(state), Move::Reinforce { territory: Territory::Argentina, armies: 1 });
+        let state = GameState::from_parts(Player::A, GamePhase::Reinforce(3), board(3, 3));
+        assert_eq!(bot.make_move(state), Move::Reinforce { territory: Territory::Alaska, armies: 1 });
+
+        // Ratios of 2.9 and 2.1 are both 2
+        let state = GameState::from_parts(Player::A, GamePhase::Attack, board(29, 21));
+        assert_eq!(bot.make_move(state), Move::Attack { from: Territory::Argentina, to: Territory::Brazil, attacking: 20 });
+        let state = GameState::from_parts(Player::A, GamePhase::Attack, board(29, 19));
+        assert_eq!(bot.make_move(state), Move::Attack { from: Territory::Alaska, to: Territory::Kamchatka, attacking: 28 });
+        let state = GameState::from_parts(Player::A, GamePhase::Attack, board(19, 19));
+        assert_eq!(bot.make_move(state), Move::Pass);
     }
 }
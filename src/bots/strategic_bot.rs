@@ -0,0 +1,221 @@
+use std::collections::VecDeque;
+
+use itertools::Itertools;
+use strum::{EnumCount, IntoEnumIterator};
+
+use crate::game_state::{GamePhase, GameState, Move};
+use crate::player::Player;
+use crate::territories::{Continent, Territory};
+
+use super::Bot;
+
+// Attacks with fewer attacking armies per defender are only made to complete or break a continent
+const MIN_ODDS: f64 = 1.5;
+const MIN_ODDS_CONTINENT: f64 = 1.0;
+
+// Plays for continents: it targets the continent with the best bonus for what it costs to take and hold, reinforces the
+// borders of held and targeted continents, breaks enemy bonuses and moves idle interior armies to the front.
+#[derive(Default)]
+pub struct StrategicBot;
+
+fn territories_of(continent: Continent) -> impl Iterator<Item = Territory> {
+    Territory::iter().filter(move |t| t.continent() == continent)
+}
+
+fn holds(state: &GameState, player: Player, continent: Continent) -> bool {
+    territories_of(continent).all(|t| state.territory_state(t).player() == player)
+}
+
+fn enemy_neighbors(state: &GameState, territory: Territory) -> impl Iterator<Item = Territory> + '_ {
+    let player = state.territory_state(territory).player();
+    territory.neighbors().into_iter().filter(move |&n| state.territory_state(n).player() != player)
+}
+
+// Territories of the continent that an attack can come from outside of it
+fn borders(continent: Continent) -> usize {
+    territories_of(continent).filter(|t| t.neighbors().iter().any(|n| n.continent() != continent)).count()
+}
+
+// The continent not yet held where the bonus is highest compared to the enemy armies in it and the borders to defend.
+// Only continents the player already has a foothold in or next to are considered.
+fn target_continent(state: &GameState, player: Player) -> Option<Continent> {
+    Continent::iter()
+        .filter(|&c| !holds(state, player, c))
+        .filter(|&c| territories_of(c).any(|t| state.territory_state(t).player() == player || t.neighbors().iter().any(|&n| state.territory_state(n).player() == player)))
+        .map(|c| {
+            let cost: u32 = territories_of(c).filter(|&t| state.territory_state(t).player() != player).map(|t| state.territory_state(t).armies() as u32 + 1).sum();
+            (c, GameState::continent_bonus(c) as f64 / (cost + borders(c) as u32) as f64)
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(c, _)| c)
+}
+
+// Steps from every territory of the player to the nearest one with an enemy neighbor, moving through own territories
+fn distances_to_front(state: &GameState, player: Player) -> [Option<usize>; Territory::COUNT] {
+    let mut distances = [None; Territory::COUNT];
+    let mut queue = VecDeque::new();
+    for territory in state.territories_states_of_player(player) {
+        if enemy_neighbors(state, territory.territory()).next().is_some() {
+            distances[territory.territory() as usize] = Some(0);
+            queue.push_back(territory.territory());
+        }
+    }
+    while let Some(territory) = queue.pop_front() {
+        let distance = distances[territory as usize].unwrap();
+        for neighbor in territory.neighbors() {
+            if state.territory_state(neighbor).player() == player && distances[neighbor as usize].is_none() {
+                distances[neighbor as usize] = Some(distance + 1);
+                queue.push_back(neighbor);
+            }
+        }
+    }
+    distances
+}
+
+impl StrategicBot {
+    // How much a front territory matters: borders of held continents are chokepoints, next to the target continent
+    // armies are staged for the attack, next to an enemy continent they can break its bonus
+    fn importance(state: &GameState, player: Player, target: Option<Continent>, territory: Territory) -> f64 {
+        let mut importance = 1.0;
+        let continent = territory.continent();
+        if holds(state, player, continent) {
+            importance += GameState::continent_bonus(continent) as f64;
+        }
+        for neighbor in enemy_neighbors(state, territory) {
+            let neighbor_continent = neighbor.continent();
+            if Some(neighbor_continent) == target {
+                importance += GameState::continent_bonus(neighbor_continent) as f64 / 2.0;
+            }
+            if holds(state, player.next(), neighbor_continent) {
+                importance += GameState::continent_bonus(neighbor_continent) as f64 / 2.0;
+            }
+        }
+        importance
+    }
+
+    fn reinforce(state: &GameState) -> Move {
+        let player = state.current_player();
+        let target = target_continent(state, player);
+        let candidates = state.territories_states_of_player(player).into_iter().filter(|t| t.state().armies() < u8::MAX).collect::<Vec<_>>();
+        let front = candidates.iter().filter(|t| enemy_neighbors(state, t.territory()).next().is_some()).collect::<Vec<_>>();
+        let candidates = if front.is_empty() { candidates.iter().collect() } else { front };
+
+        // One army at a time, so the next one sees the threat after this one is placed
+        let best = candidates.into_iter().max_by(|a, b| {
+            let score = |t: Territory| {
+                let armies = state.territory_state(t).armies() as f64;
+                let threat: f64 = enemy_neighbors(state, t).map(|n| state.territory_state(n).armies() as f64).sum();
+                Self::importance(state, player, target, t) * (1.0 + threat) / armies
+            };
+            score(a.territory()).total_cmp(&score(b.territory()))
+        }).unwrap();
+        Move::Reinforce { territory: best.territory(), armies: 1 }
+    }
+
+    // Attacking into the target continent, completing a continent and breaking an enemy one are worth the bonus
+    fn attack_value(state: &GameState, player: Player, target: Option<Continent>, to: Territory) -> (f64, bool) {
+        let continent = to.continent();
+        let completes = territories_of(continent).all(|t| t == to || state.territory_state(t).player() == player);
+        let breaks = holds(state, player.next(), continent);
+        let mut value = 1.0;
+        if Some(continent) == target {
+            value += GameState::continent_bonus(continent) as f64 / 2.0;
+        }
+        if completes || breaks {
+            value += GameState::continent_bonus(continent) as f64;
+        }
+        (value, completes || breaks)
+    }
+
+    fn attack(state: &GameState) -> Option<Move> {
+        let player = state.current_player();
+        let target = target_continent(state, player);
+        state.territories_states_of_player(player).iter()
+            .filter(|from| from.state().armies() >= 2)
+            .flat_map(|from| enemy_neighbors(state, from.territory()).map(move |to| (from.territory(), to)).collect::<Vec<_>>())
+            .filter_map(|(from, to)| {
+                let odds = (state.territory_state(from).armies() - 1) as f64 / state.territory_state(to).armies() as f64;
+                let (value, continent) = Self::attack_value(state, player, target, to);
+                (odds >= if continent { MIN_ODDS_CONTINENT } else { MIN_ODDS }).then_some((from, to, value * odds))
+            })
+            .max_by(|a, b| a.2.total_cmp(&b.2))
+            .map(|(from, to, _)| {
+                // Armies stay behind when other enemies border the attacking territory
+                let available = state.territory_state(from).armies() - 1;
+                let other_threats = enemy_neighbors(state, from).filter(|&n| n != to).map(|n| state.territory_state(n).armies()).max().unwrap_or(0);
+                let attacking = available.saturating_sub(other_threats).max(state.territory_state(to).armies() + 1).min(available);
+                Move::Attack { from, to, attacking }
+            })
+    }
+
+    // Moves the largest idle interior army one step towards the front, ending the turn
+    fn fortify(state: &GameState) -> Move {
+        let player = state.current_player();
+        let target = target_continent(state, player);
+        let distances = distances_to_front(state, player);
+        let interior = state.territories_states_of_player(player).into_iter()
+            .filter(|t| t.state().armies() >= 2 && distances[t.territory() as usize].is_some_and(|d| d > 0))
+            .max_by_key(|t| t.state().armies());
+        let Some(from) = interior else {
+            return Move::Pass;
+        };
+
+        let to = from.territory().neighbors().into_iter()
+            .filter(|&n| state.territory_state(n).player() == player && state.territory_state(n).armies() < u8::MAX)
+            .min_set_by_key(|&n| distances[n as usize].unwrap_or(usize::MAX))
+            .into_iter()
+            .max_by(|&a, &b| Self::importance(state, player, target, a).total_cmp(&Self::importance(state, player, target, b)));
+        match to {
+            Some(to) => {
+                let armies = (from.state().armies() - 1).min(u8::MAX - state.territory_state(to).armies());
+                Move::Fortify { from: from.territory(), to, armies }
+            }
+            None => Move::Pass,
+        }
+    }
+}
+
+impl Bot for StrategicBot {
+    fn make_move(&mut self, game_state: GameState) -> Move {
+        match game_state.phase() {
+            GamePhase::Reinforce(_) => Self::reinforce(&game_state),
+            GamePhase::Attack => Self::attack(&game_state).unwrap_or_else(|| Self::fortify(&game_state)),
+            GamePhase::Fortify => Self::fortify(&game_state),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use strum::{EnumCount, IntoEnumIterator};
+
+    use crate::arena;
+    use crate::bots::random_bot::RandomBot;
+    use crate::bots::Bot;
+    use crate::game;
+    use crate::game_state::{GamePhase, GameState, Move, TerritoryState};
+    use crate::player::Player;
+    use crate::territories::{Continent, Territory};
+
+    use super::StrategicBot;
+
+    #[test]
+    fn breaks_enemy_continent() {
+        let mut territories = [TerritoryState::new(Player::A, 1); Territory::COUNT];
+        for territory in Territory::iter().filter(|t| t.continent() == Continent::Europe).chain([Territory::Alaska, Territory::Greenland]) {
+            territories[territory as usize] = TerritoryState::new(Player::B, 1);
+        }
+        territories[Territory::Ural as usize] = TerritoryState::new(Player::A, 5);
+        territories[Territory::Alberta as usize] = TerritoryState::new(Player::A, 5);
+
+        // North America is cheaper to take, but Europe pays the opponent more
+        let state = GameState::from_parts(Player::A, GamePhase::Attack, territories);
+        assert!(matches!(StrategicBot.make_move(state), Move::Attack { from: Territory::Ural, to: Territory::Ukraine, .. }));
+    }
+
+    #[test]
+    fn beats_random_bot() {
+        let result = arena::play_games(4, game::evaluate_win, StrategicBot::default, || RandomBot {}).unwrap();
+        assert!(result.wins(Player::A) > result.wins(Player::B));
+    }
+}
@@ -0,0 +1,333 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use strum::EnumCount;
+
+use crate::adjudicator::Adjudicator;
+use crate::bots::Float;
+use crate::bots::{Bot, MoveContext};
+use crate::game::{GameConfig, GameResult};
+use crate::game_state::{GamePhase, GameState, GameStateDuringInitialPlacement, Move};
+use crate::player::Player;
+use crate::territories::{Territory, NEIGHBORS};
+
+// Actions are numbered in this order: reinforce one army on a territory, attack with 1 to 3 armies along a directed
+// edge of the map, fortify all but one army along a directed edge, pass
+const REINFORCE: usize = 0;
+const ATTACK: usize = REINFORCE + Territory::COUNT;
+const FORTIFY: usize = ATTACK + 3 * 2 * NEIGHBORS.len();
+const PASS: usize = FORTIFY + 2 * NEIGHBORS.len();
+pub const ACTIONS: usize = PASS + 1;
+
+// Own and enemy armies of every territory, seen from the agent, then the phase and the reinforcements left
+pub const OBSERVATION: usize = 2 * Territory::COUNT + 4;
+
+pub type Observation = Vec<Float>;
+
+// Weights of the parts of the reward of a step. Win is paid once at the end, +1 for a win and -1 for a loss,
+// the others are paid for the change over the step, including the moves of the opponent.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Reward {
+    pub win: Float,
+    // Own minus enemy territories
+    pub territories: Float,
+    // Own minus enemy reinforcements per turn
+    pub income: Float,
+}
+
+impl Default for Reward {
+    fn default() -> Self {
+        Self { win: 1.0, territories: 0.0, income: 0.0 }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StepInfo {
+    pub round: u16,
+    // Set once the game is over
+    pub result: Option<GameResult>,
+    // Moves of the opponent since the last step
+    pub opponent_moves: Vec<Move>,
+    // Why the game ended early, e.g. an opponent that failed
+    pub forfeit: Option<String>,
+}
+
+pub fn observation(state: &GameState, player: Player) -> Observation {
+    let mut observation = vec![0.0; OBSERVATION];
+    for (idx, territory) in state.territory_states().iter().enumerate() {
+        let offset = if territory.player() == player { 0 } else { Territory::COUNT };
+        observation[offset + idx] = territory.armies() as Float / u8::MAX as Float;
+    }
+    let (phase, reinforcements) = match state.phase() {
+        GamePhase::Reinforce(reinforcements) => (0, reinforcements),
+        GamePhase::Attack => (1, 0),
+        GamePhase::Fortify => (2, 0),
+    };
+    observation[2 * Territory::COUNT + phase] = 1.0;
+    observation[2 * Territory::COUNT + 3] = reinforcements as Float / u8::MAX as Float;
+    observation
+}
+
+// A stepping environment around `GameState` for reinforcement learning. The agent plays one seat, the moves of the
+// opponent are played inside `reset` and `step` until it is the agent's turn again. The seed decides the placement
+// and the dice, a random opponent still uses its own randomness.
+pub struct RiskEnv<B: Bot> {
+    opponent: B,
+    agent: Player,
+    config: GameConfig,
+    adjudicator: Adjudicator,
+    reward: Reward,
+    rng: StdRng,
+    state: GameState,
+    round: u16,
+    moves_this_turn: usize,
+    result: Option<GameResult>,
+}
+
+impl<B: Bot> RiskEnv<B> {
+    pub fn new(opponent: B) -> Self {
+        let mut rng = StdRng::from_entropy();
+        let state = GameStateDuringInitialPlacement::new().place_random_with(&mut rng).start();
+        Self { opponent, agent: Player::A, config: GameConfig::default(), adjudicator: Adjudicator::default(), reward: Reward::default(), rng, state, round: 0, moves_this_turn: 0, result: None }
+    }
+
+    // Seat of the agent, Player::A moves first
+    pub fn agent(mut self, agent: Player) -> Self {
+        self.agent = agent;
+        self
+    }
+
+    // Only the round and move limits are used, there is no time control
+    pub fn config(mut self, config: GameConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn adjudicator(mut self, adjudicator: Adjudicator) -> Self {
+        self.adjudicator = adjudicator;
+        self
+    }
+
+    pub fn reward(mut self, reward: Reward) -> Self {
+        self.reward = reward;
+        self
+    }
+
+    // Starts a new game, the same seed gives the same game for the same actions and opponent moves
+    pub fn reset(&mut self, seed: Option<u64>) -> (Observation, StepInfo) {
+        self.rng = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        self.state = GameStateDuringInitialPlacement::new().place_random_with(&mut self.rng).start();
+        self.round = 0;
+        self.moves_this_turn = 0;
+        self.result = None;
+        self.opponent.new_game(self.agent.next(), &self.config);
+
+        let mut info = StepInfo::default();
+        self.play_opponent(&mut info);
+        info.round = self.round;
+        (self.observation(), info)
+    }
+
+    // Plays an action of the agent and the answer of the opponent. Illegal actions are an error and change nothing.
+    pub fn step(&mut self, action: usize) -> Result<(Observation, Float, bool, StepInfo), String> {
+        if self.result.is_some() {
+            return Err("The game is over, call reset".to_string());
+        }
+        let move_to_play = self.action_move(action).filter(|m| self.state.legal_moves().contains(m))
+            .ok_or_else(|| format!("Illegal action {} in phase {:?}", action, self.state.phase()))?;
+
+        let before = self.score();
+        let mut info = StepInfo::default();
+        self.moves_this_turn += 1;
+        if self.moves_this_turn > self.config.max_moves_per_turn {
+            self.finish(GameResult::Win(self.agent.next()), Some(format!("{} made more than {} moves in one turn", self.agent, self.config.max_moves_per_turn)), &mut info);
+        } else {
+            self.play(move_to_play, &mut info);
+            self.play_opponent(&mut info);
+        }
+
+        let after = self.score();
+        let mut reward = self.reward.territories * (after.0 - before.0) + self.reward.income * (after.1 - before.1);
+        reward += match self.result {
+            Some(GameResult::Win(player)) if player == self.agent => self.reward.win,
+            Some(GameResult::Win(_)) => -self.reward.win,
+            _ => 0.0,
+        };
+        info.round = self.round;
+        Ok((self.observation(), reward, self.result.is_some(), info))
+    }
+
+    pub fn state(&self) -> &GameState {
+        &self.state
+    }
+
+    pub fn observation(&self) -> Observation {
+        observation(&self.state, self.agent)
+    }
+
+    // Which actions are legal now, every legal move that has an action is one
+    pub fn action_mask(&self) -> Vec<bool> {
+        let mut mask = vec![false; ACTIONS];
+        for move_to_play in self.state.legal_moves() {
+            if let Some(action) = self.move_action(&move_to_play) {
+                mask[action] = true;
+            }
+        }
+        mask
+    }
+
+    pub fn action_move(&self, action: usize) -> Option<Move> {
+        let edge = |idx: usize| {
+            let (a, b) = NEIGHBORS[idx / 2];
+            [(a, b), (b, a)][idx % 2]
+        };
+        match action {
+            _ if action < ATTACK => Some(Move::Reinforce { territory: Territory::try_from((action - REINFORCE) as u8).ok()?, armies: 1 }),
+            _ if action < FORTIFY => {
+                let (from, to) = edge((action - ATTACK) / 3);
+                Some(Move::Attack { from, to, attacking: ((action - ATTACK) % 3 + 1) as u8 })
+            }
+            _ if action < PASS => {
+                let (from, to) = edge(action - FORTIFY);
+                Some(Move::Fortify { from, to, armies: self.state.territory_state(from).armies().saturating_sub(1) })
+            }
+            PASS => Some(Move::Pass),
+            _ => None,
+        }
+    }
+
+    // None for moves without an action, e.g. reinforcing more than one army at once
+    pub fn move_action(&self, move_to_play: &Move) -> Option<usize> {
+        let edge = |from: Territory, to: Territory| {
+            NEIGHBORS.iter().position(|&(a, b)| (a, b) == (from, to) || (b, a) == (from, to)).map(|idx| 2 * idx + (NEIGHBORS[idx].0 != from) as usize)
+        };
+        match *move_to_play {
+            Move::Reinforce { territory, armies: 1 } => Some(REINFORCE + territory as usize),
+            Move::Attack { from, to, attacking: attacking @ 1..=3 } => edge(from, to).map(|idx| ATTACK + 3 * idx + attacking as usize - 1),
+            Move::Fortify { from, to, armies } if armies.checked_add(1) == Some(self.state.territory_state(from).armies()) => edge(from, to).map(|idx| FORTIFY + idx),
+            Move::Pass => Some(PASS),
+            _ => None,
+        }
+    }
+
+    // Territories and income of the agent minus those of the opponent
+    fn score(&self) -> (Float, Float) {
+        let [agent, opponent] = [self.agent, self.agent.next()];
+        let territories = self.state.territories_states_of_player(agent).len() as Float - self.state.territories_states_of_player(opponent).len() as Float;
+        let income = self.state.number_of_reinforcements(agent) as Float - self.state.number_of_reinforcements(opponent) as Float;
+        (territories, income)
+    }
+
+    fn play(&mut self, move_to_play: Move, info: &mut StepInfo) {
+        let player = self.state.current_player();
+        self.state = match self.state.apply_move(&move_to_play) {
+            Ok(result) => result.random_state_with(&mut self.rng),
+            Err(err) => return self.finish(GameResult::Win(player.next()), Some(format!("{} played illegal move {:?}: {:?}", player, move_to_play, err)), info),
+        };
+        self.opponent.observe(player, &move_to_play, &self.state);
+
+        if self.state.current_player() != player || self.state.is_finished() {
+            self.round += 1;
+            self.moves_this_turn = 0;
+        }
+        if self.state.is_finished() || self.round >= self.config.max_rounds {
+            self.finish(self.adjudicator.adjudicate(self.state), None, info);
+        }
+    }
+
+    fn play_opponent(&mut self, info: &mut StepInfo) {
+        let opponent = self.agent.next();
+        while self.result.is_none() && self.state.current_player() == opponent {
+            let move_to_play = match self.opponent.try_make_move(self.state, &MoveContext::default()) {
+                Ok(move_to_play) => move_to_play,
+                Err(err) => return self.finish(GameResult::Win(self.agent), Some(format!("{} failed: {}", opponent, err)), info),
+            };
+            self.moves_this_turn += 1;
+            if self.moves_this_turn > self.config.max_moves_per_turn {
+                return self.finish(GameResult::Win(self.agent), Some(format!("{} made more than {} moves in one turn", opponent, self.config.max_moves_per_turn)), info);
+            }
+            info.opponent_moves.push(move_to_play);
+            self.play(move_to_play, info);
+        }
+    }
+
+    fn finish(&mut self, result: GameResult, forfeit: Option<String>, info: &mut StepInfo) {
+        self.result = Some(result);
+        info.result = Some(result);
+        info.forfeit = forfeit;
+        self.opponent.game_over(result);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    use crate::bots::random_bot::RandomBot;
+    use crate::bots::strategic_bot::StrategicBot;
+    use crate::bots::Bot;
+    use crate::game::GameResult;
+    use crate::game_state::Move;
+    use crate::player::Player;
+    use crate::territories::NEIGHBORS;
+
+    use super::{Reward, RiskEnv, ACTIONS, OBSERVATION};
+
+    // Plays random legal actions until the end, returning the observations, the total reward and the result
+    fn play_out<B: Bot>(env: &mut RiskEnv<B>, seed: u64) -> (Vec<Vec<f32>>, f32, GameResult) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let (observation, _) = env.reset(Some(seed));
+        let mut observations = vec![observation];
+        let mut total = 0.0;
+        loop {
+            let mask = env.action_mask();
+            assert_eq!(mask.len(), ACTIONS);
+            let legal = (0..ACTIONS).filter(|&a| mask[a]).collect::<Vec<_>>();
+            let (observation, reward, done, info) = env.step(legal[rng.gen_range(0..legal.len())]).unwrap();
+            assert_eq!(observation.len(), OBSERVATION);
+            observations.push(observation);
+            total += reward;
+            if done {
+                assert!(env.step(legal[0]).is_err());
+                return (observations, total, info.result.unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn seeded_episodes_repeat() {
+        let mut env = RiskEnv::new(StrategicBot).agent(Player::B);
+        let (observations, total, result) = play_out(&mut env, 7);
+        assert_eq!(play_out(&mut env, 7), (observations, total, result));
+        assert_eq!(total, match result {
+            GameResult::Win(Player::B) => 1.0,
+            GameResult::Win(Player::A) => -1.0,
+            GameResult::Draw => 0.0,
+        });
+    }
+
+    #[test]
+    fn masks_match_legal_moves() {
+        let mut env = RiskEnv::new(RandomBot {}).reward(Reward { win: 0.0, territories: 1.0, income: 0.0 });
+        env.reset(Some(1));
+        for action in 0..ACTIONS {
+            let legal = env.action_move(action).is_some_and(|m| env.state().legal_moves().contains(&m));
+            assert_eq!(env.action_mask()[action], legal, "{}", action);
+            if let Some(m) = env.action_move(action) {
+                assert_eq!(env.move_action(&m), Some(action));
+            }
+        }
+        assert!(env.step(ACTIONS).is_err());
+        let (from, to) = NEIGHBORS[0];
+        assert_eq!(env.move_action(&Move::Fortify { from, to, armies: u8::MAX }), None);
+
+        // Reinforcing one army neither ends the turn nor changes territories
+        let action = (0..ACTIONS).find(|&a| env.action_mask()[a]).unwrap();
+        let (_, reward, done, info) = env.step(action).unwrap();
+        assert!(info.opponent_moves.is_empty() && !done);
+        assert_eq!(reward, 0.0);
+    }
+}
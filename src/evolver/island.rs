@@ -0,0 +1,121 @@
+use std::thread;
+
+use crate::bots::Float;
+
+use super::{Evaluator, Evolver, Mutator};
+
+pub struct IslandEvolver<E, M, const LENGTH: usize, const POPULATION: usize>
+where E: Evaluator<LENGTH>, M: Mutator {
+    islands: Vec<Evolver<E, M, LENGTH, POPULATION>>,
+    migration_interval: usize,
+    migrants: usize,
+    generation: usize,
+}
+
+impl<E, M, const LENGTH: usize, const POPULATION: usize> IslandEvolver<E, M, LENGTH, POPULATION>
+where E: Evaluator<LENGTH> + Send, M: Mutator + Send {
+    pub fn new(islands: Vec<Evolver<E, M, LENGTH, POPULATION>>, migration_interval: usize, migrants: usize) -> Self {
+        assert!(!islands.is_empty(), "Need at least one island");
+        assert!(migration_interval > 0, "Migration interval has to be at least one generation");
+        IslandEvolver { islands, migration_interval, migrants: std::cmp::min(migrants, POPULATION), generation: 0 }
+    }
+
+    pub fn islands(&self) -> &[Evolver<E, M, LENGTH, POPULATION>] {
+        &self.islands
+    }
+
+    pub fn islands_mut(&mut self) -> &mut [Evolver<E, M, LENGTH, POPULATION>] {
+        &mut self.islands
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    // Evolves every island on its own thread and returns the best genome of each island, or None if an island was
+    // cancelled, then the generation is not counted and nothing migrates
+    pub fn evolve_step(&mut self) -> Option<Vec<[Float; LENGTH]>> {
+        let best_genomes = thread::scope(|scope| {
+            let handles = self.islands.iter_mut().map(|island| scope.spawn(move || island.evolve_step())).collect::<Vec<_>>();
+            handles.into_iter().map(|handle| handle.join().expect("Island panicked")).collect::<Option<Vec<_>>>()
+        })?;
+
+        self.generation += 1;
+        if self.generation.is_multiple_of(self.migration_interval) {
+            self.migrate();
+        }
+
+        Some(best_genomes)
+    }
+
+    // Ring topology: the best genomes of every island join the next one and compete in its next evaluation
+    fn migrate(&mut self) {
+        if self.islands.len() < 2 || self.migrants == 0 {
+            return;
+        }
+
+        let emigrants = self.islands.iter().map(|island| island.best_genomes(self.migrants)).collect::<Vec<_>>();
+        for (idx, genomes) in emigrants.iter().enumerate() {
+            let target = (idx + 1) % self.islands.len();
+            self.islands[target].immigrate(genomes);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{bots::Float, evolver::{EvaluationResult, Evaluator, Evolver, ProbabilityMutator}};
+
+    use super::IslandEvolver;
+
+    struct ConstantEvaluator {
+        value: Float,
+    }
+
+    impl<const LENGTH: usize> Evaluator<LENGTH> for ConstantEvaluator {
+        fn initialize(&mut self) -> [Float; LENGTH] {
+            [self.value; LENGTH]
+        }
+
+        fn evaluate(&self, a: &[Float], b: &[Float]) -> EvaluationResult {
+            let sum_a: Float = a.iter().sum();
+            let sum_b: Float = b.iter().sum();
+            if sum_a > sum_b {
+                EvaluationResult::A
+            } else if sum_b > sum_a {
+                EvaluationResult::B
+            } else {
+                EvaluationResult::Draw
+            }
+        }
+    }
+
+    #[test]
+    fn best_genome_migrates_to_next_island() {
+        let islands: Vec<Evolver<_, ProbabilityMutator, 2, 5>> = vec![
+            Evolver::new(ConstantEvaluator { value: 1.0 }),
+            Evolver::new(ConstantEvaluator { value: 0.0 }),
+        ];
+        let mut evolver = IslandEvolver::new(islands, 1, 1);
+
+        let best = evolver.evolve_step();
+        assert_eq!(best, Some(vec![[1.0, 1.0], [0.0, 0.0]]));
+
+        let best = evolver.evolve_step();
+        assert_eq!(best, Some(vec![[1.0, 1.0], [1.0, 1.0]]));
+        assert_eq!(evolver.generation(), 2);
+    }
+
+    #[test]
+    fn no_migration_before_interval() {
+        let islands: Vec<Evolver<_, ProbabilityMutator, 2, 5>> = vec![
+            Evolver::new(ConstantEvaluator { value: 1.0 }),
+            Evolver::new(ConstantEvaluator { value: 0.0 }),
+        ];
+        let mut evolver = IslandEvolver::new(islands, 3, 1);
+
+        evolver.evolve_step();
+        let best = evolver.evolve_step();
+        assert_eq!(best, Some(vec![[1.0, 1.0], [0.0, 0.0]]));
+    }
+}
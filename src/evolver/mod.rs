@@ -2,7 +2,7 @@ use std::ops::Range;
 
 use rand::Rng;
 
-use crate::bots::neural_bot::Float;
+use crate::bots::Float;
 
 #[derive(PartialEq, Eq, Debug)]
 pub enum EvaluationResult {
@@ -14,6 +14,11 @@ pub enum EvaluationResult {
 pub trait Evaluator<const LENGTH: usize> {
     fn initialize(&mut self) -> [Float; LENGTH];
     fn evaluate(&self, a: &[Float], b: &[Float]) -> EvaluationResult;
+
+    // Checked after every evaluation, a cancelled generation leaves the population as it was
+    fn is_cancelled(&self) -> bool {
+        false
+    }
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -25,7 +30,7 @@ struct GenomeStats<const LENGTH: usize> {
 pub mod transformations {
     use rand::Rng;
 
-    use crate::bots::neural_bot::Float;
+    use crate::bots::Float;
 
     pub fn average<const LENGTH: usize>(a: &[Float; LENGTH], b: &[Float; LENGTH]) -> [Float; LENGTH] {
         let mut result = [0.0; LENGTH];
@@ -57,8 +62,17 @@ pub mod transformations {
     }
 }
 
+pub type Transformation<const LENGTH: usize> = Box<dyn Fn(&[Float; LENGTH], &[Float; LENGTH]) -> [Float; LENGTH] + Send>;
+
+// Called with the number of finished evaluations of a generation and their total
+pub type EvolverProgress = Box<dyn Fn(usize, usize) + Send>;
+
 pub trait Mutator {
     fn mutate(&self, genome: &mut [Float]);
+
+    fn describe(&self) -> String {
+        String::new()
+    }
 }
 
 pub struct ProbabilityMutator {
@@ -75,6 +89,10 @@ impl Mutator for ProbabilityMutator {
             }
         }
     }
+
+    fn describe(&self) -> String {
+        format!("probability={} range={}..{}", self.probability, self.range.start, self.range.end)
+    }
 }
 
 pub struct Evolver<E, M, const LENGTH: usize, const POPULATION: usize>
@@ -82,7 +100,8 @@ where E: Evaluator<LENGTH>, M: Mutator {
     population: [GenomeStats<LENGTH>; POPULATION],
     evaluator: E,
     mutator: Option<M>,
-    transformation: Box<dyn Fn(&[Float; LENGTH], &[Float; LENGTH]) -> [Float; LENGTH]>
+    transformation: Transformation<LENGTH>,
+    progress: Option<EvolverProgress>,
 }
 
 impl<E, M, const LENGTH: usize, const POPULATION: usize> Evolver<E, M, LENGTH, POPULATION>
@@ -91,14 +110,14 @@ where E: Evaluator<LENGTH>, M: Mutator {
         Evolver::with_transformation(evaluator, Box::new(transformations::average))
     }
 
-    pub fn with_transformation(evaluator: E, transformation: Box<dyn Fn(&[Float; LENGTH], &[Float; LENGTH]) -> [Float; LENGTH]>) -> Self {
+    pub fn with_transformation(evaluator: E, transformation: Transformation<LENGTH>) -> Self {
         let mut evaluator = evaluator;
         let mut population = Vec::with_capacity(POPULATION);
         for _ in 0..POPULATION {
             population.push(GenomeStats { genome: evaluator.initialize(), fitness: 0 });
         }
         let population: [GenomeStats<LENGTH>; POPULATION] = population.try_into().unwrap();
-        Evolver { population, evaluator, mutator: None, transformation }
+        Evolver { population, evaluator, mutator: None, transformation, progress: None }
     }
 
     pub fn mutator(&self) -> &Option<M> {
@@ -109,28 +128,42 @@ where E: Evaluator<LENGTH>, M: Mutator {
         self.mutator = mutator;
     }
 
-    pub fn evolve_step(&mut self) -> [Float; LENGTH] {
-        for state in &mut self.population {
-            state.fitness = 0;
-        }
+    pub fn on_progress<F>(mut self, callback: F) -> Self
+    where
+    F: Fn(usize, usize) + Send + 'static {
+        self.progress = Some(Box::new(callback));
+        self
+    }
 
+    // Returns the best genome, or None if the evaluator was cancelled before every genome was evaluated
+    pub fn evolve_step(&mut self) -> Option<[Float; LENGTH]> {
+        let mut fitness = [0; POPULATION];
+        let total = POPULATION * (POPULATION - 1) / 2;
+        let mut evaluated = 0;
         for idx_a in 0..(POPULATION - 1) {
             for idx_b in (idx_a + 1)..POPULATION {
-                // println!("Evaluating {} vs {}", idx_a, idx_b);
-                let idx_winner = match self.evaluator.evaluate(&self.population[idx_a].genome, &self.population[idx_b].genome) {
-                    EvaluationResult::A => idx_a,
-                    EvaluationResult::B => idx_b,
-                    EvaluationResult::Draw => continue,
-                };
-                // println!("Winner: {}", idx_winner);
-                self.population[idx_winner].fitness += 1
+                let result = self.evaluator.evaluate(&self.population[idx_a].genome, &self.population[idx_b].genome);
+                if self.evaluator.is_cancelled() {
+                    return None;
+                }
+                evaluated += 1;
+                if let Some(progress) = &self.progress {
+                    progress(evaluated, total);
+                }
+                match result {
+                    EvaluationResult::A => fitness[idx_a] += 1,
+                    EvaluationResult::B => fitness[idx_b] += 1,
+                    EvaluationResult::Draw => {}
+                }
             }
         }
 
+        for (state, fitness) in self.population.iter_mut().zip(fitness) {
+            state.fitness = fitness;
+        }
         self.population.sort_by(|a, b| a.fitness.cmp(&b.fitness).reverse());
         let best_genome = self.population[0].genome;
 
-
         // Mutate everyone randomly
         if let Some(mutator) = &self.mutator {
             let threshold = POPULATION * 2 / 10;
@@ -150,16 +183,54 @@ where E: Evaluator<LENGTH>, M: Mutator {
             }
         }
 
-        best_genome
+        Some(best_genome)
+    }
+
+    // Fitness of the last evaluation, sorted best first
+    pub fn fitnesses(&self) -> Vec<usize> {
+        self.population.iter().map(|state| state.fitness).collect()
+    }
+
+    // Mean euclidean distance between all pairs of genomes in the population
+    pub fn diversity(&self) -> f64 {
+        let mut total = 0.0;
+        let mut pairs = 0;
+        for idx_a in 0..POPULATION {
+            for idx_b in (idx_a + 1)..POPULATION {
+                let a = &self.population[idx_a].genome;
+                let b = &self.population[idx_b].genome;
+                total += a.iter().zip(b).map(|(x, y)| ((x - y) as f64).powi(2)).sum::<f64>().sqrt();
+                pairs += 1;
+            }
+        }
+        if pairs == 0 { 0.0 } else { total / pairs as f64 }
+    }
+
+    pub fn best_genomes(&self, count: usize) -> Vec<[Float; LENGTH]> {
+        self.population.iter().take(count).map(|state| state.genome).collect()
+    }
+
+    // Overwrites the end of the population, which after breeding holds the newest offspring that were not evaluated
+    // yet, or without a mutator the lowest ranked genomes of the last evaluation
+    pub fn immigrate(&mut self, genomes: &[[Float; LENGTH]]) {
+        let start = POPULATION - std::cmp::min(genomes.len(), POPULATION);
+        for (state, genome) in self.population[start..].iter_mut().zip(genomes) {
+            state.genome = *genome;
+        }
     }
 }
 
+pub mod island;
+pub mod rule_weights;
 
 #[cfg(test)]
 mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
     use rand::Rng;
 
-    use crate::{bots::neural_bot::Float, evolver::ProbabilityMutator};
+    use crate::{bots::Float, evolver::ProbabilityMutator};
 
     use super::{EvaluationResult, Evaluator, Evolver};
 
@@ -239,6 +310,56 @@ mod tests {
         let evaluator = OnceEvaluator::<2> { generated: false };
         let mut evolver: Evolver<_, ProbabilityMutator, 2, 10> = Evolver::new(evaluator);
         let fittest = evolver.evolve_step();
-        assert_eq!(fittest, [1.0, 1.0]);
+        assert_eq!(fittest, Some([1.0, 1.0]));
+        assert_eq!(evolver.fitnesses()[0], 9);
+    }
+
+    // Cancels after a number of evaluations
+    struct CancellingEvaluator {
+        evaluations: AtomicUsize,
+        cancel_after: usize,
+    }
+
+    impl Evaluator<2> for CancellingEvaluator {
+        fn initialize(&mut self) -> [Float; 2] {
+            MaxEvaluator::<2> {}.initialize()
+        }
+
+        fn evaluate(&self, a: &[Float], b: &[Float]) -> EvaluationResult {
+            self.evaluations.fetch_add(1, Ordering::SeqCst);
+            MaxEvaluator::<2> {}.evaluate(a, b)
+        }
+
+        fn is_cancelled(&self) -> bool {
+            self.evaluations.load(Ordering::SeqCst) >= self.cancel_after
+        }
+    }
+
+    #[test]
+    fn progress_and_cancel() {
+        let reported = Arc::new(AtomicUsize::new(0));
+        let counter = reported.clone();
+        let evaluator = CancellingEvaluator { evaluations: AtomicUsize::new(0), cancel_after: usize::MAX };
+        let mut evolver: Evolver<_, ProbabilityMutator, 2, 5> = Evolver::new(evaluator).on_progress(move |evaluated, total| {
+            assert_eq!(total, 10);
+            counter.store(evaluated, Ordering::SeqCst);
+        });
+        assert!(evolver.evolve_step().is_some());
+        assert_eq!(reported.load(Ordering::SeqCst), 10);
+
+        let evaluator = CancellingEvaluator { evaluations: AtomicUsize::new(0), cancel_after: 3 };
+        let mut evolver: Evolver<_, ProbabilityMutator, 2, 5> = Evolver::new(evaluator);
+        evolver.set_mutator(Some(ProbabilityMutator { probability: 1.0, range: 0.0..1.0 }));
+        let population = evolver.best_genomes(5);
+        assert_eq!(evolver.evolve_step(), None);
+        assert_eq!(evolver.best_genomes(5), population);
+        assert_eq!(evolver.fitnesses(), vec![0; 5]);
+    }
+
+    #[test]
+    fn test_diversity() {
+        let evaluator = OnceEvaluator::<2> { generated: false };
+        let evolver: Evolver<_, ProbabilityMutator, 2, 2> = Evolver::new(evaluator);
+        assert!((evolver.diversity() - Float::sqrt(2.0) as f64).abs() < 1e-6);
     }
 }
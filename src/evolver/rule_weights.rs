@@ -0,0 +1,51 @@
+use rand::Rng;
+
+use crate::adjudicator::Adjudicator;
+use crate::arena::{self, ArenaOptions};
+use crate::bots::Float;
+use crate::bots::rule_based_bot::{RuleBasedBot, RuleWeights};
+use crate::player::Player;
+
+use super::{EvaluationResult, Evaluator};
+
+// Lets the evolver tune the weights of `RuleBasedBot`. Genomes are the weights as multiples of their defaults,
+// see `RuleWeights::from_genome`, so mutations between 0 and about 3 cover sensible values.
+pub struct RuleWeightsEvaluator {
+    games: u32,
+    adjudicator: Adjudicator,
+    options: ArenaOptions,
+    initialized: usize,
+}
+
+impl RuleWeightsEvaluator {
+    pub fn new(games: u32, adjudicator: Adjudicator, options: ArenaOptions) -> Self {
+        Self { games, adjudicator, options, initialized: 0 }
+    }
+}
+
+impl Evaluator<{ RuleWeights::LENGTH }> for RuleWeightsEvaluator {
+    // The first genome is the hand tuned bot, the others are spread around it
+    fn initialize(&mut self) -> [Float; RuleWeights::LENGTH] {
+        self.initialized += 1;
+        if self.initialized == 1 {
+            return RuleWeights::default().to_genome();
+        }
+        let mut rng = rand::thread_rng();
+        std::array::from_fn(|_| rng.gen_range(0.5..1.5))
+    }
+
+    fn evaluate(&self, a: &[Float], b: &[Float]) -> EvaluationResult {
+        let (weights_a, weights_b) = (RuleWeights::from_genome(a), RuleWeights::from_genome(b));
+        let adjudicator = self.adjudicator;
+        let result = arena::play_games_with_options(self.games, move |state| adjudicator.adjudicate(state), || RuleBasedBot::new(weights_a), || RuleBasedBot::new(weights_b), &self.options).unwrap();
+        match result.winner() {
+            Some(Player::A) => EvaluationResult::A,
+            Some(Player::B) => EvaluationResult::B,
+            None => EvaluationResult::Draw,
+        }
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.options.is_cancelled()
+    }
+}
@@ -0,0 +1,343 @@
+// C API for embedding the rules, the header is include/risk.h and ffi/test_risk.c shows how it is used.
+//
+// Game states and bots are opaque handles that the caller owns and frees with the matching free function.
+// Pointers passed in have to be valid or NULL, functions return an error or NULL for NULL pointers.
+// Territories are their index in the map order, see risk_territory_name, players are 0 and 1.
+#![allow(clippy::missing_safety_doc)]
+
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+use once_cell::sync::Lazy;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use strum::{EnumCount, IntoEnumIterator};
+
+use crate::bots::registry::BotRegistry;
+use crate::bots::Bot;
+use crate::game_state::{GamePhase, GameState, GameStateDuringInitialPlacement, Move, TerritoryState};
+use crate::player::Player;
+use crate::territories::Territory;
+
+// Raised whenever a function or struct changes in a way that breaks existing callers
+pub const RISK_API_VERSION: u32 = 1;
+// A literal, so it shows up in the header
+pub const RISK_TERRITORIES: usize = 42;
+const _: () = assert!(RISK_TERRITORIES == Territory::COUNT);
+
+pub const RISK_PHASE_REINFORCE: u8 = 0;
+pub const RISK_PHASE_ATTACK: u8 = 1;
+pub const RISK_PHASE_FORTIFY: u8 = 2;
+
+pub const RISK_MOVE_PASS: u8 = 0;
+pub const RISK_MOVE_REINFORCE: u8 = 1;
+pub const RISK_MOVE_FORTIFY: u8 = 2;
+pub const RISK_MOVE_ATTACK: u8 = 3;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RiskStatus {
+    Ok = 0,
+    NullPointer = -1,
+    InvalidArgument = -2,
+    IllegalMove = -3,
+    BotFailed = -4,
+}
+
+// `from` is the reinforced territory of a reinforcement, `to` and `armies` are 0 where the move has none
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RiskMove {
+    pub kind: u8,
+    pub from: u8,
+    pub to: u8,
+    pub armies: u8,
+}
+
+pub struct RiskGameState(GameState);
+
+pub struct RiskBot(Box<dyn Bot>);
+
+static TERRITORY_NAMES: Lazy<Vec<CString>> = Lazy::new(|| Territory::iter().map(|t| CString::new(t.to_string()).unwrap()).collect());
+
+impl From<Move> for RiskMove {
+    fn from(move_to_play: Move) -> Self {
+        match move_to_play {
+            Move::Pass => RiskMove { kind: RISK_MOVE_PASS, ..RiskMove::default() },
+            Move::Reinforce { territory, armies } => RiskMove { kind: RISK_MOVE_REINFORCE, from: territory as u8, to: 0, armies },
+            Move::Fortify { from, to, armies } => RiskMove { kind: RISK_MOVE_FORTIFY, from: from as u8, to: to as u8, armies },
+            Move::Attack { from, to, attacking } => RiskMove { kind: RISK_MOVE_ATTACK, from: from as u8, to: to as u8, armies: attacking },
+        }
+    }
+}
+
+impl TryFrom<RiskMove> for Move {
+    type Error = RiskStatus;
+
+    fn try_from(m: RiskMove) -> Result<Self, Self::Error> {
+        let territory = |index: u8| Territory::try_from(index).map_err(|_| RiskStatus::InvalidArgument);
+        Ok(match m.kind {
+            RISK_MOVE_PASS => Move::Pass,
+            RISK_MOVE_REINFORCE => Move::Reinforce { territory: territory(m.from)?, armies: m.armies },
+            RISK_MOVE_FORTIFY => Move::Fortify { from: territory(m.from)?, to: territory(m.to)?, armies: m.armies },
+            RISK_MOVE_ATTACK => Move::Attack { from: territory(m.from)?, to: territory(m.to)?, attacking: m.armies },
+            _ => return Err(RiskStatus::InvalidArgument),
+        })
+    }
+}
+
+fn player(index: u8) -> Option<Player> {
+    match index {
+        0 => Some(Player::A),
+        1 => Some(Player::B),
+        _ => None,
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn risk_api_version() -> u32 {
+    RISK_API_VERSION
+}
+
+// Static string, NULL for an invalid territory
+#[no_mangle]
+pub extern "C" fn risk_territory_name(territory: u8) -> *const c_char {
+    TERRITORY_NAMES.get(territory as usize).map_or(ptr::null(), |name| name.as_ptr())
+}
+
+// Packs a move into 32 bits: kind, from, to and armies from the highest byte down
+#[no_mangle]
+pub extern "C" fn risk_move_encode(m: RiskMove) -> u32 {
+    u32::from_be_bytes([m.kind, m.from, m.to, m.armies])
+}
+
+// Fails for codes that are no valid move
+#[no_mangle]
+pub unsafe extern "C" fn risk_move_decode(code: u32, out: *mut RiskMove) -> RiskStatus {
+    let Some(out) = out.as_mut() else {
+        return RiskStatus::NullPointer;
+    };
+    let [kind, from, to, armies] = code.to_be_bytes();
+    let m = RiskMove { kind, from, to, armies };
+    match Move::try_from(m) {
+        Ok(_) => {
+            *out = m;
+            RiskStatus::Ok
+        }
+        Err(err) => err,
+    }
+}
+
+// A random start, the same for the same seed
+#[no_mangle]
+pub extern "C" fn risk_state_new(seed: u64) -> *mut RiskGameState {
+    let state = GameStateDuringInitialPlacement::new().place_random_with(&mut StdRng::seed_from_u64(seed)).start();
+    Box::into_raw(Box::new(RiskGameState(state)))
+}
+
+// `players` and `armies` have RISK_TERRITORIES entries, NULL for invalid players or phases
+#[no_mangle]
+pub unsafe extern "C" fn risk_state_from_parts(current_player: u8, phase: u8, reinforcements: u8, players: *const u8, armies: *const u8) -> *mut RiskGameState {
+    if players.is_null() || armies.is_null() {
+        return ptr::null_mut();
+    }
+    let (players, armies) = (std::slice::from_raw_parts(players, RISK_TERRITORIES), std::slice::from_raw_parts(armies, RISK_TERRITORIES));
+    let phase = match phase {
+        RISK_PHASE_REINFORCE => GamePhase::Reinforce(reinforcements),
+        RISK_PHASE_ATTACK => GamePhase::Attack,
+        RISK_PHASE_FORTIFY => GamePhase::Fortify,
+        _ => return ptr::null_mut(),
+    };
+    let mut territories = [TerritoryState::new(Player::A, 0); Territory::COUNT];
+    for (idx, territory) in territories.iter_mut().enumerate() {
+        let Some(player) = player(players[idx]) else {
+            return ptr::null_mut();
+        };
+        *territory = TerritoryState::new(player, armies[idx]);
+    }
+    match player(current_player) {
+        Some(current_player) => Box::into_raw(Box::new(RiskGameState(GameState::from_parts(current_player, phase, territories)))),
+        None => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn risk_state_clone(state: *const RiskGameState) -> *mut RiskGameState {
+    match state.as_ref() {
+        Some(state) => Box::into_raw(Box::new(RiskGameState(state.0))),
+        None => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn risk_state_free(state: *mut RiskGameState) {
+    if !state.is_null() {
+        drop(Box::from_raw(state));
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn risk_state_current_player(state: *const RiskGameState) -> u8 {
+    state.as_ref().map_or(0, |state| state.0.current_player() as u8)
+}
+
+// One of RISK_PHASE_*, `reinforcements` may be NULL
+#[no_mangle]
+pub unsafe extern "C" fn risk_state_phase(state: *const RiskGameState, reinforcements: *mut u8) -> u8 {
+    let Some(state) = state.as_ref() else {
+        return 0;
+    };
+    let (phase, left) = match state.0.phase() {
+        GamePhase::Reinforce(left) => (RISK_PHASE_REINFORCE, left),
+        GamePhase::Attack => (RISK_PHASE_ATTACK, 0),
+        GamePhase::Fortify => (RISK_PHASE_FORTIFY, 0),
+    };
+    if let Some(reinforcements) = reinforcements.as_mut() {
+        *reinforcements = left;
+    }
+    phase
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn risk_state_territory(state: *const RiskGameState, territory: u8, player: *mut u8, armies: *mut u8) -> RiskStatus {
+    let (Some(state), Some(player), Some(armies)) = (state.as_ref(), player.as_mut(), armies.as_mut()) else {
+        return RiskStatus::NullPointer;
+    };
+    let Ok(territory) = Territory::try_from(territory) else {
+        return RiskStatus::InvalidArgument;
+    };
+    let territory_state = state.0.territory_state(territory);
+    *player = territory_state.player() as u8;
+    *armies = territory_state.armies();
+    RiskStatus::Ok
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn risk_state_is_finished(state: *const RiskGameState) -> bool {
+    state.as_ref().is_some_and(|state| state.0.is_finished())
+}
+
+// Writes up to `capacity` moves and returns how many there are, so a call with capacity 0 asks for the size
+#[no_mangle]
+pub unsafe extern "C" fn risk_state_legal_moves(state: *const RiskGameState, out: *mut RiskMove, capacity: usize) -> usize {
+    let Some(state) = state.as_ref() else {
+        return 0;
+    };
+    let moves = state.0.legal_moves();
+    if !out.is_null() {
+        for (idx, m) in moves.iter().take(capacity).enumerate() {
+            *out.add(idx) = RiskMove::from(*m);
+        }
+    }
+    moves.len()
+}
+
+// Rolls the dice of an attack with `seed`, the same seed gives the same outcome. The new state is written to `out`.
+#[no_mangle]
+pub unsafe extern "C" fn risk_state_apply_move(state: *const RiskGameState, m: RiskMove, seed: u64, out: *mut *mut RiskGameState) -> RiskStatus {
+    let (Some(state), Some(out)) = (state.as_ref(), out.as_mut()) else {
+        return RiskStatus::NullPointer;
+    };
+    let move_to_play = match Move::try_from(m) {
+        Ok(move_to_play) => move_to_play,
+        Err(err) => return err,
+    };
+    match state.0.apply_move(&move_to_play) {
+        Ok(result) => {
+            *out = Box::into_raw(Box::new(RiskGameState(result.random_state_with(&mut StdRng::seed_from_u64(seed)))));
+            RiskStatus::Ok
+        }
+        Err(_) => RiskStatus::IllegalMove,
+    }
+}
+
+// Any bot of the registry, e.g. "strategic" or "neural:weights.bin", NULL for unknown bots
+#[no_mangle]
+pub unsafe extern "C" fn risk_bot_new(spec: *const c_char) -> *mut RiskBot {
+    let Some(spec) = spec.as_ref().and_then(|_| CStr::from_ptr(spec).to_str().ok()) else {
+        return ptr::null_mut();
+    };
+    match BotRegistry::default().create(spec) {
+        Ok(bot) => Box::into_raw(Box::new(RiskBot(bot))),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn risk_bot_free(bot: *mut RiskBot) {
+    if !bot.is_null() {
+        drop(Box::from_raw(bot));
+    }
+}
+
+// A panicking bot is reported as RISK_STATUS_BOT_FAILED instead of unwinding into C
+#[no_mangle]
+pub unsafe extern "C" fn risk_bot_make_move(bot: *mut RiskBot, state: *const RiskGameState, out: *mut RiskMove) -> RiskStatus {
+    let (Some(bot), Some(state), Some(out)) = (bot.as_mut(), state.as_ref(), out.as_mut()) else {
+        return RiskStatus::NullPointer;
+    };
+    match panic::catch_unwind(AssertUnwindSafe(|| bot.0.make_move(state.0))) {
+        Ok(m) => {
+            *out = RiskMove::from(m);
+            RiskStatus::Ok
+        }
+        Err(_) => RiskStatus::BotFailed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ptr;
+
+    use crate::game_state::Move;
+    use crate::territories::Territory;
+
+    use super::*;
+
+    // Copy the generated header over include/risk.h after changing the API
+    #[test]
+    fn header_is_up_to_date() {
+        let generated = include_str!(concat!(env!("OUT_DIR"), "/risk.h"));
+        let committed = include_str!("../include/risk.h");
+        assert!(generated == committed, "include/risk.h is out of date, the generated header is {}/risk.h", env!("OUT_DIR"));
+    }
+
+    #[test]
+    fn moves_roundtrip() {
+        let moves = [Move::Pass, Move::Reinforce { territory: Territory::Japan, armies: 3 }, Move::Attack { from: Territory::Alaska, to: Territory::Kamchatka, attacking: 2 }];
+        for m in moves {
+            let mut decoded = RiskMove::default();
+            assert_eq!(unsafe { risk_move_decode(risk_move_encode(m.into()), &mut decoded) }, RiskStatus::Ok);
+            assert_eq!(Move::try_from(decoded), Ok(m));
+        }
+        assert_eq!(unsafe { risk_move_decode(0x0400_0000, &mut RiskMove::default()) }, RiskStatus::InvalidArgument);
+        assert_eq!(unsafe { risk_move_decode(0, ptr::null_mut()) }, RiskStatus::NullPointer);
+    }
+
+    #[test]
+    fn seeded_apply() {
+        unsafe {
+            let players = [0, 1].repeat(Territory::COUNT / 2);
+            let armies = [3; Territory::COUNT];
+            let state = risk_state_from_parts(0, RISK_PHASE_ATTACK, 0, players.as_ptr(), armies.as_ptr());
+            let attack = RiskMove { kind: RISK_MOVE_ATTACK, from: Territory::Alaska as u8, to: Territory::NorthwestTerritory as u8, armies: 2 };
+
+            let territories = |seed| {
+                let mut after = ptr::null_mut();
+                assert_eq!(risk_state_apply_move(state, attack, seed, &mut after), RiskStatus::Ok);
+                let (mut player, mut armies) = (0, 0);
+                risk_state_territory(after, Territory::NorthwestTerritory as u8, &mut player, &mut armies);
+                risk_state_free(after);
+                (player, armies)
+            };
+            assert!((0..20).all(|seed| territories(seed) == territories(seed)));
+            assert!((0..20).map(territories).any(|t| t != territories(0)));
+
+            let mut after = ptr::null_mut();
+            let illegal = RiskMove { kind: RISK_MOVE_ATTACK, from: Territory::Alaska as u8, to: Territory::Japan as u8, armies: 2 };
+            assert_eq!(risk_state_apply_move(state, illegal, 0, &mut after), RiskStatus::IllegalMove);
+            risk_state_free(state);
+        }
+    }
+}
@@ -1,5 +1,14 @@
 use std::fs::{self, create_dir_all};
+use std::panic::{self, AssertUnwindSafe};
 use std::path::Path;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+use strum::{EnumCount, IntoEnumIterator};
 
 fn recreate_folder<P: AsRef<Path>>(path: P) -> std::io::Result<()> {
     if path.as_ref().exists() {
@@ -8,37 +17,145 @@ fn recreate_folder<P: AsRef<Path>>(path: P) -> std::io::Result<()> {
     create_dir_all(path)
 }
 
-use crate::game_state::draw_map::DrawMapOptions;
+use crate::game_record::GameRecord;
+use crate::game_stats::GameStats;
+#[cfg(feature = "render")]
+use crate::game_state::DrawMapOptions;
 use crate::game_state::{GameState, GameStateDuringInitialPlacement, Move, MoveApplyErr};
 use crate::player::Player;
-use crate::bots::Bot;
+use crate::bots::{Bot, BotError, MoveContext};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum GameResult {
     Win(Player),
     Draw,
 }
 
+// Everything in here is caused by one of the bots, so the game counts as forfeited by `player`
+#[derive(Debug)]
+pub enum GameError {
+    IllegalMove { player: Player, state: GameState, move_played: Move, err: MoveApplyErr },
+    TooManyMoves { player: Player, state: GameState },
+    BotPanicked { player: Player, state: GameState, message: String },
+    BotFailed { player: Player, state: GameState, err: BotError },
+}
+
+impl GameError {
+    pub fn player(&self) -> Player {
+        match self {
+            GameError::IllegalMove { player, .. } => *player,
+            GameError::TooManyMoves { player, .. } => *player,
+            GameError::BotPanicked { player, .. } => *player,
+            GameError::BotFailed { player, .. } => *player,
+        }
+    }
+
+    pub fn state(&self) -> &GameState {
+        match self {
+            GameError::IllegalMove { state, .. } => state,
+            GameError::TooManyMoves { state, .. } => state,
+            GameError::BotPanicked { state, .. } => state,
+            GameError::BotFailed { state, .. } => state,
+        }
+    }
+}
+
+impl std::fmt::Display for GameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GameError::IllegalMove { player, move_played, err, .. } => write!(f, "{} played illegal move {:?}: {:?}", player, move_played, err),
+            GameError::TooManyMoves { player, .. } => write!(f, "{} made too many moves in one turn", player),
+            GameError::BotPanicked { player, message, .. } => write!(f, "Bot of {} panicked: {}", player, message),
+            GameError::BotFailed { player, err, .. } => write!(f, "Bot of {} failed: {}", player, err),
+        }
+    }
+}
+
+pub(crate) fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeoutPenalty {
+    #[default]
+    Forfeit,
+    // The move of the bot is replaced by a random legal move
+    RandomMove,
+}
+
+impl FromStr for TimeoutPenalty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().replace('-', "_").as_str() {
+            "forfeit" => Ok(TimeoutPenalty::Forfeit),
+            "random_move" | "random" => Ok(TimeoutPenalty::RandomMove),
+            _ => Err(format!("Unknown timeout penalty '{}', expected forfeit or random_move", s)),
+        }
+    }
+}
+
+// No limits by default. Both limits can be combined, the move then has to be done within the smaller one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TimeControl {
+    // Limit for every single move
+    pub move_ms: Option<u64>,
+    // Clock of each player for the whole game
+    pub game_ms: Option<u64>,
+    // Added to the clock after every move
+    pub increment_ms: u64,
+    pub penalty: TimeoutPenalty,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameConfig {
+    // Unfinished games are handed to the adjudicator after this many rounds
+    pub max_rounds: u16,
+    // A player that makes more moves than this in one turn forfeits
+    pub max_moves_per_turn: usize,
+    pub time_control: TimeControl,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self { max_rounds: 200, max_moves_per_turn: 200, time_control: TimeControl::default() }
+    }
+}
+
 pub struct Game<BotA: Bot, BotB: Bot> {
+    config: GameConfig,
     round: u16,
     bot_a: BotA,
     bot_b: BotB,
     game_state: GameState,
+    moves_this_turn: usize,
+    clocks: [Option<Duration>; Player::COUNT],
+    // Whether the bots were told about the start and the end of the game
+    started: bool,
+    over: bool,
+    stats: GameStats,
+    record: Option<GameRecord>,
+    // Rolls the dice, and picks the move of a bot that timed out with TimeoutPenalty::RandomMove
+    rng: StdRng,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct PlayOptions {
     filename: Option<String>,
     debug_output: bool,
 }
 
-impl Default for PlayOptions {
-    fn default() -> Self {
-        Self { filename: None, debug_output: false }
-    }
-}
-
 impl PlayOptions {
+    #[cfg(feature = "render")]
     pub fn save_map_images(mut self, folder: &str) -> Self {
         self.filename = Some(folder.to_string());
         self
@@ -60,20 +177,173 @@ pub fn evaluate_win(game_state: GameState) -> GameResult {
 
 impl<BotA: Bot, BotB: Bot> Game<BotA, BotB> {
     pub fn new(bot_a: BotA, bot_b: BotB) -> Self {
-        Self { round: 0, bot_a, bot_b, game_state: GameStateDuringInitialPlacement::new().place_random().start() }
+        Self::from_state(bot_a, bot_b, GameStateDuringInitialPlacement::new().place_random().start())
+    }
+
+    pub fn from_state(bot_a: BotA, bot_b: BotB, game_state: GameState) -> Self {
+        Self { config: GameConfig::default(), round: 0, bot_a, bot_b, game_state, moves_this_turn: 0, clocks: [None; Player::COUNT], started: false, over: false, stats: GameStats::new(&game_state), record: None, rng: StdRng::seed_from_u64(0) }
+            .with_seed(rand::random())
+    }
+
+    // The same seed rolls the same dice for the same moves
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self.stats.seed = Some(seed);
+        self
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.stats.seed.expect("Games always have a seed")
+    }
+
+    pub fn with_config(mut self, config: GameConfig) -> Self {
+        self.config = config;
+        self.clocks = [config.time_control.game_ms.map(Duration::from_millis); Player::COUNT];
+        if let Some(record) = &mut self.record {
+            record.config = config;
+        }
+        self
     }
 
-    pub fn play_round<Evaluator>(&mut self, evaluate_result: &Evaluator, options: PlayOptions) -> Result<(Option<GameResult>, Vec<Move>), MoveApplyErr>
+    // Keeps every move and the resulting state, so the game can be saved and replayed
+    pub fn record_moves(mut self, bot_a: &str, bot_b: &str) -> Self {
+        self.record = Some(GameRecord::new(bot_a, bot_b, self.config, self.game_state));
+        self
+    }
+
+    pub fn config(&self) -> &GameConfig {
+        &self.config
+    }
+
+    // Some once a player holds every territory or the round limit is hit
+    pub fn check_result<Evaluator>(&mut self, evaluate_result: &Evaluator) -> Option<GameResult>
     where
     Evaluator: Fn(GameState) -> GameResult {
-        if self.game_state.is_finished() {
-            return Ok((Some(evaluate_result(self.game_state)), vec![]))
+        if !self.game_state.is_finished() && self.round < self.config.max_rounds {
+            return None;
+        }
+        let result = evaluate_result(self.game_state);
+        if let Some(record) = &mut self.record {
+            record.finish(result);
+        }
+        self.end(result);
+        Some(result)
+    }
+
+    fn bot_mut(&mut self, player: Player) -> &mut dyn Bot {
+        if player == Player::A { &mut self.bot_a } else { &mut self.bot_b }
+    }
+
+    // Calls one of the hooks of a bot, a panic in there forfeits the game like one in `make_move`
+    fn notify(&mut self, player: Player, hook: impl FnOnce(&mut dyn Bot)) -> Result<(), GameError> {
+        let state = self.game_state;
+        let bot = self.bot_mut(player);
+        panic::catch_unwind(AssertUnwindSafe(|| hook(bot))).map_err(|payload| GameError::BotPanicked { player, state, message: panic_message(payload.as_ref()) })
+    }
+
+    fn start(&mut self) -> Result<(), GameError> {
+        if self.started {
+            return Ok(());
+        }
+        self.started = true;
+        let config = self.config;
+        for player in Player::iter() {
+            self.notify(player, |bot| bot.new_game(player, &config))?;
+        }
+        Ok(())
+    }
+
+    // The game is decided at this point, so panics are ignored
+    fn end(&mut self, result: GameResult) {
+        if self.over {
+            return;
+        }
+        self.over = true;
+        for player in Player::iter() {
+            self.notify(player, |bot| bot.game_over(result)).ok();
+        }
+    }
+
+    pub fn move_context(&self, player: Player) -> MoveContext {
+        let clock = self.clocks[player as usize];
+        let move_limit = self.config.time_control.move_ms.map(Duration::from_millis);
+        let time_for_move = match (move_limit, clock) {
+            (Some(limit), Some(clock)) => Some(limit.min(clock)),
+            (limit, clock) => limit.or(clock),
+        };
+        MoveContext { time_for_move, clock }
+    }
+
+    // Plays a single move of the current player, the round ends when the turn passes to the other player
+    pub fn play_move(&mut self) -> Result<Move, GameError> {
+        let result = self.start().and_then(|_| self.play_bot_move());
+        if let Err(err) = &result {
+            self.end(GameResult::Win(err.player().next()));
+        }
+        result
+    }
+
+    fn play_bot_move(&mut self) -> Result<Move, GameError> {
+        let player = self.game_state.current_player();
+        let state = self.game_state;
+        let context = self.move_context(player);
+        let bot = self.bot_mut(player);
+        let start = Instant::now();
+        let result = panic::catch_unwind(AssertUnwindSafe(|| bot.try_make_move(state, &context)));
+        let elapsed = start.elapsed();
+
+        let over_time = context.time_for_move.is_some_and(|limit| elapsed > limit);
+        let move_to_play = match result {
+            Ok(Ok(move_to_play)) if !over_time => Some(move_to_play),
+            Ok(Ok(_)) | Ok(Err(BotError::Timeout(_))) => None,
+            Ok(Err(err)) => return Err(GameError::BotFailed { player, state, err }),
+            Err(payload) => return Err(GameError::BotPanicked { player, state, message: panic_message(payload.as_ref()) }),
+        };
+        self.stats.record_time(player, elapsed, move_to_play.is_none());
+        if let Some(clock) = &mut self.clocks[player as usize] {
+            *clock = clock.saturating_sub(elapsed) + Duration::from_millis(self.config.time_control.increment_ms);
+        }
+        let move_to_play = match (move_to_play, self.config.time_control.penalty) {
+            (Some(move_to_play), _) => move_to_play,
+            (None, TimeoutPenalty::Forfeit) => return Err(GameError::BotFailed { player, state, err: BotError::Timeout(context.time_for_move.unwrap_or(elapsed)) }),
+            (None, TimeoutPenalty::RandomMove) => *state.legal_moves().choose(&mut self.rng).expect("There is always a legal move"),
+        };
+
+        self.moves_this_turn += 1;
+        if self.moves_this_turn > self.config.max_moves_per_turn {
+            return Err(GameError::TooManyMoves { player, state });
+        }
+
+        self.game_state = match self.game_state.apply_move(&move_to_play) {
+            Ok(result) => result.random_state_with(&mut self.rng),
+            Err(err) => return Err(GameError::IllegalMove { player, state, move_played: move_to_play, err }),
+        };
+        self.stats.record_move(self.round + 1, player, &state, &move_to_play, &self.game_state);
+        if let Some(record) = &mut self.record {
+            record.push(self.round + 1, player, move_to_play, self.game_state);
         }
+        let after = self.game_state;
+        for observer in Player::iter() {
+            self.notify(observer, |bot| bot.observe(player, &move_to_play, &after))?;
+        }
+
+        if self.game_state.current_player() != player || self.game_state.is_finished() {
+            self.round += 1;
+            self.moves_this_turn = 0;
+            self.stats.record_round(self.round, &self.game_state);
+        }
+
+        Ok(move_to_play)
+    }
 
-        if self.round >= 200 {
-            return Ok((Some(evaluate_result(self.game_state)), vec![]))
+    pub fn play_round<Evaluator>(&mut self, evaluate_result: &Evaluator, options: PlayOptions) -> Result<(Option<GameResult>, Vec<Move>), GameError>
+    where
+    Evaluator: Fn(GameState) -> GameResult {
+        if let Some(result) = self.check_result(evaluate_result) {
+            return Ok((Some(result), vec![]))
         }
 
+        #[cfg(feature = "render")]
         if let Some(filename) = options.filename {
             self.game_state.draw_map(DrawMapOptions::default().filename(&filename)).expect("Could not save map to file");
         }
@@ -87,29 +357,19 @@ impl<BotA: Bot, BotB: Bot> Game<BotA, BotB> {
         }
 
         let mut moves_played = vec![];
-        let player = self.game_state.current_player();
-        let bot: &mut dyn Bot = if player == Player::A { &mut self.bot_a } else { &mut self.bot_b };
-        while self.game_state.current_player() == player && !self.game_state.is_finished() {
-            let move_to_play = bot.make_move(self.game_state.clone());
-            moves_played.push(move_to_play);
-
-            if moves_played.len() > 200 {
-                return Err(MoveApplyErr::TooManyMoves);
-            }
-
+        let round = self.round;
+        while self.round == round {
+            let move_to_play = self.play_move()?;
             if options.debug_output {
                 println!("  {:?}", move_to_play);
             }
-
-            self.game_state = self.game_state.apply_move(&move_to_play).unwrap().random_state_by_probability();
+            moves_played.push(move_to_play);
         }
 
-        self.round += 1;
-
         Ok((None, moves_played))
     }
 
-    pub fn play_until_end<Evaluator>(&mut self, evaluate_result: &Evaluator, options: &PlayOptions) -> Result<(u16, GameResult), MoveApplyErr>
+    pub fn play_until_end<Evaluator>(&mut self, evaluate_result: &Evaluator, options: &PlayOptions) -> Result<(u16, GameResult), GameError>
     where
     Evaluator: Fn(GameState) -> GameResult {
         if let Some(folder) = &options.filename {
@@ -130,4 +390,103 @@ impl<BotA: Bot, BotB: Bot> Game<BotA, BotB> {
     pub fn round(&self) -> u16 {
         self.round
     }
+
+    pub fn state(&self) -> &GameState {
+        &self.game_state
+    }
+
+    // Time left on the clock of `player`, None without a game clock
+    pub fn clock(&self, player: Player) -> Option<Duration> {
+        self.clocks[player as usize]
+    }
+
+    pub fn stats(&self) -> &GameStats {
+        &self.stats
+    }
+
+    pub fn record(&self) -> Option<&GameRecord> {
+        self.record.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use crate::bots::random_bot::RandomBot;
+    use crate::bots::strategic_bot::StrategicBot;
+    use crate::bots::Bot;
+    use crate::game_state::{GameState, Move};
+    use crate::player::Player;
+
+    use super::{evaluate_win, Game, GameConfig, GameResult, PlayOptions};
+
+    #[derive(Debug, PartialEq)]
+    enum Event {
+        NewGame(Player),
+        Observe(Player),
+        GameOver(GameResult),
+    }
+
+    struct HookBot {
+        events: Arc<Mutex<Vec<Event>>>,
+        passes_only: bool,
+    }
+
+    impl Bot for HookBot {
+        fn make_move(&mut self, game_state: GameState) -> Move {
+            if self.passes_only { Move::Pass } else { RandomBot {}.make_move(game_state) }
+        }
+
+        fn new_game(&mut self, seat: Player, _config: &GameConfig) {
+            self.events.lock().unwrap().push(Event::NewGame(seat));
+        }
+
+        fn observe(&mut self, player: Player, _move_played: &Move, _state: &GameState) {
+            self.events.lock().unwrap().push(Event::Observe(player));
+        }
+
+        fn game_over(&mut self, result: GameResult) {
+            self.events.lock().unwrap().push(Event::GameOver(result));
+        }
+    }
+
+    #[test]
+    fn hooks_are_called() {
+        let (events_a, events_b) = (Arc::new(Mutex::new(vec![])), Arc::new(Mutex::new(vec![])));
+        let bot_a = HookBot { events: events_a.clone(), passes_only: false };
+        let bot_b = HookBot { events: events_b.clone(), passes_only: false };
+        let mut game = Game::new(bot_a, bot_b).with_config(GameConfig { max_rounds: 5, ..GameConfig::default() });
+        let (_, result) = game.play_until_end(&evaluate_win, &PlayOptions::default()).unwrap();
+        game.check_result(&evaluate_win);
+
+        let moves = game.stats().players.iter().map(|p| p.moves() as usize).sum::<usize>();
+        for (events, seat) in [(events_a, Player::A), (events_b, Player::B)] {
+            let events = events.lock().unwrap();
+            assert_eq!(events.first(), Some(&Event::NewGame(seat)));
+            assert_eq!(events.last(), Some(&Event::GameOver(result)));
+            assert_eq!(events.len(), moves + 2);
+        }
+    }
+
+    #[test]
+    fn same_seed_same_game() {
+        let start = Game::new(RandomBot {}, RandomBot {}).game_state;
+        let play = |seed| {
+            let mut game = Game::from_state(StrategicBot, StrategicBot, start).with_seed(seed).record_moves("strategic", "strategic");
+            game.play_until_end(&evaluate_win, &PlayOptions::default()).unwrap();
+            assert_eq!(game.seed(), seed);
+            game.record().unwrap().moves.iter().map(|m| serde_json::to_string(&m.state).unwrap()).collect::<Vec<_>>()
+        };
+        assert_eq!(play(7), play(7));
+        assert_ne!(play(7), play(8));
+    }
+
+    #[test]
+    fn game_over_after_forfeit() {
+        let events = Arc::new(Mutex::new(vec![]));
+        let mut game = Game::new(HookBot { events: events.clone(), passes_only: true }, RandomBot {});
+        assert!(game.play_move().is_err());
+        assert_eq!(*events.lock().unwrap(), vec![Event::NewGame(Player::A), Event::GameOver(GameResult::Win(Player::B))]);
+    }
 }
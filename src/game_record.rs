@@ -0,0 +1,107 @@
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::{GameConfig, GameResult};
+use crate::game_state::{GameState, Move};
+use crate::game_stats::GameStats;
+use crate::player::Player;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedMove {
+    pub round: u16,
+    pub player: Player,
+    pub move_played: Move,
+    // State after the move, including the outcome of the dice
+    pub state: GameState,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameRecord {
+    pub bot_a: String,
+    pub bot_b: String,
+    pub config: GameConfig,
+    pub initial_state: GameState,
+    pub moves: Vec<RecordedMove>,
+    pub rounds: u16,
+    pub result: Option<GameResult>,
+}
+
+impl GameRecord {
+    pub fn new(bot_a: &str, bot_b: &str, config: GameConfig, initial_state: GameState) -> Self {
+        Self { bot_a: bot_a.to_string(), bot_b: bot_b.to_string(), config, initial_state, moves: vec![], rounds: 0, result: None }
+    }
+
+    pub fn push(&mut self, round: u16, player: Player, move_played: Move, state: GameState) {
+        self.rounds = round;
+        self.moves.push(RecordedMove { round, player, move_played, state });
+    }
+
+    pub fn finish(&mut self, result: GameResult) {
+        self.result = Some(result);
+    }
+
+    pub fn state_after_round(&self, round: u16) -> GameState {
+        self.moves.iter().take_while(|m| m.round <= round).last().map_or(self.initial_state, |m| m.state)
+    }
+
+    pub fn final_state(&self) -> GameState {
+        self.moves.last().map_or(self.initial_state, |m| m.state)
+    }
+
+    // Rebuilds the statistics the game would have collected while it was played
+    pub fn stats(&self) -> GameStats {
+        let mut stats = GameStats::new(&self.initial_state);
+        let mut before = self.initial_state;
+        for (idx, m) in self.moves.iter().enumerate() {
+            stats.record_move(m.round, m.player, &before, &m.move_played, &m.state);
+            before = m.state;
+            if self.moves.get(idx + 1).map(|next| next.round) != Some(m.round) {
+                stats.record_round(m.round, &m.state);
+            }
+        }
+        stats
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(&mut writer, self)?;
+        writer.flush()
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Ok(serde_json::from_reader(BufReader::new(File::open(path)?))?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bots::random_bot::RandomBot;
+    use crate::game::{self, Game, PlayOptions};
+
+    use super::GameRecord;
+
+    #[test]
+    fn record_matches_game() {
+        let mut game = Game::new(RandomBot {}, RandomBot {}).record_moves("random", "random");
+        let (rounds, result) = game.play_until_end(&game::evaluate_win, &PlayOptions::default()).unwrap();
+        let record = game.record().unwrap().clone();
+
+        assert_eq!(record.rounds, rounds);
+        assert_eq!(record.result, Some(result));
+        assert_eq!(record.state_after_round(0).to_string(), record.initial_state.to_string());
+        assert_eq!(record.state_after_round(rounds).to_string(), record.final_state().to_string());
+
+        let stats = record.stats();
+        assert_eq!(stats.rounds, game.stats().rounds);
+        assert_eq!(stats.territories, game.stats().territories);
+        assert_eq!(stats.players[0].attack_moves, game.stats().players[0].attack_moves);
+
+        let json = serde_json::to_string(&record).unwrap();
+        let parsed: GameRecord = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.moves.len(), record.moves.len());
+        assert_eq!(parsed.final_state().to_string(), record.final_state().to_string());
+    }
+}
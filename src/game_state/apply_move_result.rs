@@ -43,7 +43,11 @@ impl ApplyMoveResult {
     }
 
     pub fn random_state_by_probability(&self) -> GameState {
-        let mut rnd: i16 = rand::thread_rng().gen_range(0..self.total_count()).try_into().unwrap();
+        self.random_state_with(&mut rand::thread_rng())
+    }
+
+    pub fn random_state_with<R: Rng>(&self, rng: &mut R) -> GameState {
+        let mut rnd: i16 = rng.gen_range(0..self.total_count()).try_into().unwrap();
         for state in self.states_with_count.iter() {
             rnd -= state.count() as i16;
             if rnd <= 0 {
@@ -53,3 +57,9 @@ impl ApplyMoveResult {
         panic!("Should not happen");
     }
 }
+
+impl Default for ApplyMoveResult {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,64 +1,25 @@
+use std::fmt;
 use std::fs::File;
-use std::io::BufReader;
-use strum::EnumCount;
-use viuer::{Config, print, };
-use cairo;
+use std::io::{BufReader, Read};
+#[cfg(feature = "terminal-preview")]
+use viuer::{Config, print};
 use image::{DynamicImage, ImageBuffer};
 use once_cell::sync::Lazy;
 
-use crate::territories::Territory;
+use crate::territories::MAP_COORDS;
 
 use super::GameState;
 
-const TERRITORY_MAP_COORDS: &[(f64, f64); Territory::COUNT] = &[
-    (45.0, 80.0), // Alaska
-    (120.0, 74.0), // Northwest Territory
-    (270.0, 55.0), // Greenland
-    (110.0, 125.0), // Alberta
-    (168.0, 128.0), // Ontario
-    (220.0, 130.0), // Quebec
-    (115.0, 180.0), // Western United States
-    (170.0, 200.0), // Eastern United States
-    (125.0, 250.0), // Central America
-    (175.0, 300.0), // Venezuela
-    (190.0, 370.0), // Peru
-    (240.0, 360.0), // Brazil
-    (200.0, 440.0), // Argentina
-
-    (335.0, 100.0), // Iceland
-    (400.0, 100.0), // Scandinavia
-    (470.0, 140.0), // Ukraine
-    (310.0, 160.0), // Great Britain
-    (390.0, 180.0), // Northern Europe
-    (335.0, 240.0), // Western Europe
-    (397.0, 235.0), // Southern Europe
-    (370.0, 340.0), // North Africa
-    (425.0, 305.0), // Egypt
-    (470.0, 380.0), // East Africa
-    (425.0, 405.0), // Congo
-    (430.0, 465.0), // South Africa
-    (505.0, 475.0), // Madagascar
-
-    (550.0, 135.0), // Ural
-    (590.0, 80.0), // Siberia
-    (655.0, 60.0), // Yakutsk
-    (720.0, 65.0), // Kamchatka
-    (640.0, 130.0), // Irkutsk
-    (650.0, 185.0), // Mongolia
-    (625.0, 235.0), // China
-    (540.0, 195.0), // Afghanistan
-    (490.0, 270.0), // Middle East
-    (580.0, 280.0), // India
-    (650.0, 310.0), // Siam
-    (660.0, 400.0), // Indonesia
-    (720.0, 375.0), // New Guinea
-    (695.0, 475.0), // Western Australia
-    (755.0, 465.0), // Eastern Australia
-    (740.0, 175.0), // Japan
-];
+// Size of the board MAP_COORDS belong to, other backgrounds are scaled to it
+const WIDTH: i32 = 800;
+const HEIGHT: i32 = 533;
+
+const MAP_PNG: &[u8] = include_bytes!("../../map.png");
 
 pub struct DrawMapOptions {
     filename: String,
+    background: Option<String>,
+    #[cfg(feature = "terminal-preview")]
     should_print: bool,
 }
 
@@ -66,6 +27,8 @@ impl Default for DrawMapOptions {
     fn default() -> Self {
         Self {
             filename: "output-map.png".to_string(),
+            background: None,
+            #[cfg(feature = "terminal-preview")]
             should_print: false,
         }
     }
@@ -77,67 +40,230 @@ impl DrawMapOptions {
         self
     }
 
+    // PNG to draw the territories on instead of the built in board
+    pub fn background(mut self, filename: &str) -> Self {
+        self.background = Some(filename.to_string());
+        self
+    }
+
+    #[cfg(feature = "terminal-preview")]
     pub fn should_print(mut self) -> Self {
         self.should_print = true;
         self
     }
 }
 
-impl GameState {
-    pub fn draw_map(&self, options: DrawMapOptions) -> Result<(), cairo::Error> {
-        const WIDTH: u32 = 800;
-        const HEIGHT: u32 = 533;
-
-        let surface = cairo::ImageSurface::create(cairo::Format::ARgb32, WIDTH.try_into().unwrap(), HEIGHT.try_into().unwrap())
-            .expect("Can't create surface");
-        {
-            let cr = cairo::Context::new(&surface).unwrap();
-
-            static MAP_IMAGE_SURFACE_DATA: Lazy<Vec<u8>> = Lazy::new(|| {
-                cairo::ImageSurface::create_from_png(&mut BufReader::new(File::open("map.png").unwrap())).expect("Failed to load map image").take_data().unwrap().to_vec()
-            });
-
-            let map_image_surface = cairo::ImageSurface::create_for_data(MAP_IMAGE_SURFACE_DATA.clone(), cairo::Format::Rgb24, WIDTH as i32, HEIGHT as i32, 3200).expect("Can't create map image surface");
-
-            cr.set_source_surface(&map_image_surface, 0.0, 0.0)?;
-            cr.paint()?;
-            cr.select_font_face("Purisa", cairo::FontSlant::Normal, cairo::FontWeight::Normal);
-            cr.set_font_size(20.0);
-
-            for ((x, y), t) in TERRITORY_MAP_COORDS.iter().zip(&self.territories) {
-                let player = t.player;
-                cr.arc(*x, *y, 20.0, 0.0, 2.0 * std::f64::consts::PI);
-                let (red, green, blue) = player.color();
-                cr.set_source_rgb(red, green, blue);
-                cr.fill()?;
-
-                cr.set_source_rgb(1.0, 1.0, 1.0);
-                let army_text = format!("{}", t.armies);
-
-                let text_size = cr.text_extents(&army_text)?;
-                cr.move_to(*x - text_size.width() / 2.0, *y + text_size.height() / 2.0);
-                cr.show_text(&army_text)?;
-            }
+#[derive(Debug, Clone, PartialEq)]
+pub enum DrawMapError {
+    OpenBackground { filename: String, message: String },
+    // The background is not a PNG cairo can read
+    DecodeBackground { filename: String, message: String },
+    Draw(String),
+    Save { filename: String, message: String },
+    Print(String),
+}
+
+impl fmt::Display for DrawMapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DrawMapError::OpenBackground { filename, message } => write!(f, "Could not open background {}: {}", filename, message),
+            DrawMapError::DecodeBackground { filename, message } => write!(f, "Could not load background {}: {}", filename, message),
+            DrawMapError::Draw(message) => write!(f, "Could not draw map: {}", message),
+            DrawMapError::Save { filename, message } => write!(f, "Could not save {}: {}", filename, message),
+            DrawMapError::Print(message) => write!(f, "Could not print map: {}", message),
         }
+    }
+}
 
-        let data = surface.take_data().expect("Can't get surface data");
+// The pixels of a decoded PNG, surfaces themselves can't be shared between threads
+struct Background {
+    data: Vec<u8>,
+    format: cairo::Format,
+    width: i32,
+    height: i32,
+    stride: i32,
+}
 
-        let mut rgb_data = Vec::with_capacity(WIDTH as usize * HEIGHT as usize * 3);
-        for chunk in data.chunks(4) {
-            rgb_data.extend_from_slice(&[chunk[2], chunk[1], chunk[0]]);
-        }
+impl Background {
+    fn decode<R: Read>(png: &mut R) -> Result<Self, String> {
+        let surface = cairo::ImageSurface::create_from_png(png).map_err(|e| e.to_string())?;
+        let (format, width, height, stride) = (surface.format(), surface.width(), surface.height(), surface.stride());
+        let data = surface.take_data().map_err(|e| e.to_string())?.to_vec();
+        Ok(Self { data, format, width, height, stride })
+    }
+
+    fn surface(&self) -> Result<cairo::ImageSurface, cairo::Error> {
+        cairo::ImageSurface::create_for_data(self.data.clone(), self.format, self.width, self.height, self.stride)
+    }
+}
+
+static DEFAULT_BACKGROUND: Lazy<Result<Background, String>> = Lazy::new(|| Background::decode(&mut &MAP_PNG[..]));
+
+impl GameState {
+    pub fn draw_map(&self, options: DrawMapOptions) -> Result<(), DrawMapError> {
+        let draw_error = |e: cairo::Error| DrawMapError::Draw(e.to_string());
+        let background = match &options.background {
+            Some(filename) => {
+                let file = File::open(filename).map_err(|e| DrawMapError::OpenBackground { filename: filename.clone(), message: e.to_string() })?;
+                Background::decode(&mut BufReader::new(file)).map_err(|message| DrawMapError::DecodeBackground { filename: filename.clone(), message })?
+                    .surface()
+            }
+            None => DEFAULT_BACKGROUND.as_ref().map_err(|e| DrawMapError::Draw(format!("the built in map is broken: {}", e)))?.surface(),
+        }.map_err(draw_error)?;
 
-        let img = DynamicImage::ImageRgb8(ImageBuffer::from_vec(WIDTH, HEIGHT, rgb_data).unwrap());
+        let surface = cairo::ImageSurface::create(cairo::Format::ARgb32, WIDTH, HEIGHT).map_err(draw_error)?;
+        self.paint_map(&surface, &background).map_err(draw_error)?;
+        let img = to_image(surface)?;
 
-        img.save(options.filename).unwrap();
+        img.save(&options.filename).map_err(|e| DrawMapError::Save { filename: options.filename.clone(), message: e.to_string() })?;
 
+        #[cfg(feature = "terminal-preview")]
         if options.should_print {
             print(&img, &Config {
                 width: Some(80),
                 ..Default::default()
-            }).unwrap();
+            }).map_err(|e| DrawMapError::Print(e.to_string()))?;
         }
 
         Ok(())
     }
+
+    fn paint_map(&self, surface: &cairo::ImageSurface, background: &cairo::ImageSurface) -> Result<(), cairo::Error> {
+        let cr = cairo::Context::new(surface)?;
+
+        cr.save()?;
+        cr.scale(WIDTH as f64 / background.width() as f64, HEIGHT as f64 / background.height() as f64);
+        cr.set_source_surface(background, 0.0, 0.0)?;
+        cr.paint()?;
+        cr.restore()?;
+
+        cr.select_font_face("Purisa", cairo::FontSlant::Normal, cairo::FontWeight::Normal);
+        cr.set_font_size(20.0);
+
+        for ((x, y), t) in MAP_COORDS.iter().zip(&self.territories) {
+            let player = t.player;
+            cr.arc(*x, *y, 20.0, 0.0, 2.0 * std::f64::consts::PI);
+            let (red, green, blue) = player.color();
+            cr.set_source_rgb(red, green, blue);
+            cr.fill()?;
+
+            cr.set_source_rgb(1.0, 1.0, 1.0);
+            let army_text = format!("{}", t.armies);
+
+            let text_size = cr.text_extents(&army_text)?;
+            cr.move_to(*x - text_size.width() / 2.0, *y + text_size.height() / 2.0);
+            cr.show_text(&army_text)?;
+        }
+        Ok(())
+    }
+}
+
+// ARgb32 pixels are stored as BGRA, rows may be padded up to the stride
+fn to_image(surface: cairo::ImageSurface) -> Result<DynamicImage, DrawMapError> {
+    let (width, height, stride) = (surface.width() as usize, surface.height() as usize, surface.stride() as usize);
+    let data = surface.take_data().map_err(|e| DrawMapError::Draw(e.to_string()))?;
+
+    let mut rgb_data = Vec::with_capacity(width * height * 3);
+    for row in data.chunks(stride).take(height) {
+        for chunk in row[..width * 4].chunks(4) {
+            rgb_data.extend_from_slice(&[chunk[2], chunk[1], chunk[0]]);
+        }
+    }
+
+    ImageBuffer::from_vec(width as u32, height as u32, rgb_data).map(DynamicImage::ImageRgb8).ok_or_else(|| DrawMapError::Draw("surface data does not fit the image".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use image::{Rgb, RgbImage};
+
+    use crate::game_state::{GameState, GameStateDuringInitialPlacement};
+    use crate::player::Player;
+    use crate::territories::MAP_COORDS;
+
+    use super::{DrawMapError, DrawMapOptions, HEIGHT, MAP_PNG, WIDTH};
+
+    fn temp_file(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("risk-draw-map-{}-{}.png", std::process::id(), name))
+    }
+
+    fn state() -> GameState {
+        GameStateDuringInitialPlacement::new().place_random().start()
+    }
+
+    // Draws the state and returns the image that was saved
+    fn draw(state: &GameState, name: &str, options: DrawMapOptions) -> RgbImage {
+        let output = temp_file(name);
+        state.draw_map(options.filename(&output.to_string_lossy())).unwrap();
+        let img = image::open(&output).unwrap().to_rgb8();
+        std::fs::remove_file(&output).ok();
+        assert_eq!(img.dimensions(), (WIDTH as u32, HEIGHT as u32));
+        img
+    }
+
+    fn assert_color(img: &RgbImage, (x, y): (u32, u32), expected: [u8; 3]) {
+        let pixel = img.get_pixel(x, y).0;
+        assert!(pixel.iter().zip(expected).all(|(&a, b)| a.abs_diff(b) <= 2), "pixel at {},{} is {:?}, expected {:?}", x, y, pixel, expected);
+    }
+
+    // Right of the army count, inside the circle of the territory
+    fn territory_pixel(idx: usize) -> (u32, u32) {
+        let (x, y) = MAP_COORDS[idx];
+        (x as u32 + 15, y as u32)
+    }
+
+    fn player_color(player: Player) -> [u8; 3] {
+        let (red, green, blue) = player.color();
+        [(red * 255.0) as u8, (green * 255.0) as u8, (blue * 255.0) as u8]
+    }
+
+    // The built in map is embedded in the library, so it doesn't depend on the working directory
+    #[test]
+    fn default_map() {
+        let state = state();
+        let img = draw(&state, "default", DrawMapOptions::default());
+        let background = image::load_from_memory(MAP_PNG).unwrap().to_rgb8();
+        assert_color(&img, (1, 1), background.get_pixel(1, 1).0);
+        for (idx, territory) in state.territory_states().iter().enumerate() {
+            assert_color(&img, territory_pixel(idx), player_color(territory.player()));
+        }
+    }
+
+    #[test]
+    fn custom_backgrounds() {
+        let state = state();
+        let background = temp_file("background");
+        RgbImage::from_pixel(WIDTH as u32, HEIGHT as u32, Rgb([10, 200, 30])).save(&background).unwrap();
+        let img = draw(&state, "custom", DrawMapOptions::default().background(&background.to_string_lossy()));
+        assert_color(&img, (1, 1), [10, 200, 30]);
+        assert_color(&img, territory_pixel(0), player_color(state.territory_states()[0].player()));
+
+        // A smaller board is stretched to the size the territories are placed on
+        let small = RgbImage::from_fn(100, 50, |x, _| if x < 50 { Rgb([255, 0, 0]) } else { Rgb([0, 255, 0]) });
+        small.save(&background).unwrap();
+        let img = draw(&state, "scaled", DrawMapOptions::default().background(&background.to_string_lossy()));
+        assert_color(&img, (50, 20), [255, 0, 0]);
+        assert_color(&img, (WIDTH as u32 - 50, 20), [0, 255, 0]);
+        std::fs::remove_file(&background).ok();
+    }
+
+    #[test]
+    fn broken_backgrounds() {
+        let state = state();
+        let output = temp_file("broken");
+        let missing = temp_file("missing");
+        let options = DrawMapOptions::default().filename(&output.to_string_lossy()).background(&missing.to_string_lossy());
+        assert!(matches!(state.draw_map(options), Err(DrawMapError::OpenBackground { .. })));
+
+        let corrupt = temp_file("corrupt");
+        std::fs::write(&corrupt, b"not a png").unwrap();
+        let options = DrawMapOptions::default().filename(&output.to_string_lossy()).background(&corrupt.to_string_lossy());
+        let err = state.draw_map(options).unwrap_err();
+        assert!(matches!(err, DrawMapError::DecodeBackground { .. }));
+        assert!(err.to_string().contains("corrupt"));
+        std::fs::remove_file(&corrupt).ok();
+        assert!(!output.exists());
+    }
 }
@@ -30,9 +30,13 @@ impl GameStateDuringInitialPlacement {
     }
 
     pub fn place_random(&self) -> GameStateDuringInitialPlacement {
+        self.place_random_with(&mut rand::thread_rng())
+    }
+
+    // Same placement for the same seeded rng
+    pub fn place_random_with<R: Rng>(&self, rng: &mut R) -> GameStateDuringInitialPlacement {
         let mut territories = self.territories.clone();
         let mut active_player = self.current_player;
-        let mut rng = rand::thread_rng();
 
         let mut armies = [0; Player::COUNT];
         let mut territories_per_player = [0; Player::COUNT];
@@ -95,3 +99,9 @@ impl GameStateDuringInitialPlacement {
         }
     }
 }
+
+impl Default for GameStateDuringInitialPlacement {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use strum::EnumCount;
 
 use crate::player::Player;
@@ -14,13 +15,17 @@ pub struct GameStateDuringInitialPlacement {
     territories: [TerritoryStateDuringInitialPlacement; Territory::COUNT]
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 pub struct TerritoryState {
     player: Player,
     armies: u8,
 }
 
 impl TerritoryState {
+    pub fn new(player: Player, armies: u8) -> Self {
+        Self { player, armies }
+    }
+
     pub fn player(&self) -> Player {
         self.player
     }
@@ -37,7 +42,7 @@ pub struct NamedTerritoryState<'a> {
 }
 
 impl NamedTerritoryState<'_> {
-    pub fn new(territory: Territory, state: &TerritoryState) -> NamedTerritoryState {
+    pub fn new(territory: Territory, state: &TerritoryState) -> NamedTerritoryState<'_> {
         NamedTerritoryState { territory, state }
     }
 
@@ -50,21 +55,22 @@ impl NamedTerritoryState<'_> {
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum GamePhase {
     Reinforce(u8),
     Attack,
     Fortify,
 }
 
-#[derive(Copy, Clone)]
+#[derive(Debug, Copy, Clone)]
 pub struct GameState {
     current_player: Player,
     territories: [TerritoryState; Territory::COUNT],
     phase: GamePhase,
 }
 
-#[derive(PartialEq, Copy, Clone)]
+#[derive(PartialEq, Copy, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum Move {
     Pass,
     Reinforce { territory: Territory, armies: u8 },
@@ -76,7 +82,6 @@ pub enum Move {
 pub enum MoveApplyErr {
     MoveNotInPhase(Move, GamePhase),
     TooManyReinforcements,
-    TooManyMoves,
     TooManyUnitsMoved,
     TooManyUnitsDefended,
     FromTerritoryNotOwned,
@@ -87,8 +92,14 @@ pub enum MoveApplyErr {
     GameFinished
 }
 
-pub mod initial_placement;
-pub mod moves;
-pub mod apply_move_result;
-pub mod draw_map;
-pub mod display;
+mod initial_placement;
+mod moves;
+mod apply_move_result;
+#[cfg(feature = "render")]
+mod draw_map;
+mod display;
+mod serialization;
+
+pub use apply_move_result::{ApplyMoveResult, GameStateWithCount};
+#[cfg(feature = "render")]
+pub use draw_map::{DrawMapError, DrawMapOptions};
@@ -1,4 +1,4 @@
-use strum::IntoEnumIterator;
+use strum::{EnumCount, IntoEnumIterator};
 
 use itertools::Itertools;
 use counter::Counter;
@@ -13,7 +13,7 @@ struct AttackScenario {
 }
 
 impl GameState {
-    fn continent_bonus(continent: Continent) -> u8 {
+    pub fn continent_bonus(continent: Continent) -> u8 {
         match continent {
             Continent::NorthAmerica => 5,
             Continent::SouthAmerica => 2,
@@ -28,9 +28,8 @@ impl GameState {
         let territories_of_player = self.territories_states_of_player(player);
         let remaining_reinforcements: u16 = territories_of_player.iter().map(|t| u8::MAX as u16 - t.state.armies as u16).sum();
         let mut from_territories = match territories_of_player.len() {
-            // switch when ready https://github.com/rust-lang/rust/issues/37854
-            0 | 1 | 2 | 3 | 4 | 5 | 6 | 7 | 8 | 9 | 10 | 11 | 12 | 13 => 3,
-            14 | 15 | 16 => 4,
+            0..=13 => 3,
+            14..=16 => 4,
             _ => 5
         };
         for continent in self.continents_for_player(player) {
@@ -93,9 +92,9 @@ impl GameState {
         match move_to_play {
             Move::Pass => {
                 match self.phase {
-                    GamePhase::Reinforce(armies) => return Err(MoveApplyErr::MoveNotInPhase(Move::Pass, GamePhase::Reinforce(armies))),
+                    GamePhase::Reinforce(armies) => Err(MoveApplyErr::MoveNotInPhase(Move::Pass, GamePhase::Reinforce(armies))),
                     GamePhase::Attack | GamePhase::Fortify => {
-                        let mut new_state = self.clone();
+                        let mut new_state = *self;
                         new_state.set_reinforce();
                         Ok(ApplyMoveResult::single(new_state))
                     },
@@ -132,7 +131,7 @@ impl GameState {
                     return Err(MoveApplyErr::NonAdjacentTerritories);
                 }
 
-                let mut new_state = self.clone();
+                let mut new_state = *self;
                 new_state.add_armies(*from, -(*armies as i16))?;
                 new_state.add_armies(*to, *armies as i16)?;
                 new_state.set_reinforce();
@@ -167,14 +166,14 @@ impl GameState {
                     }
                 }
 
-                for (scenario, count) in scenarios {
-                    let mut new_state = self.clone();
-                    let attacker_move;
-                    if new_state.check_capture(*to, *attacking, scenario.defender_losses)? {
-                        attacker_move = *attacking;
+                // Sorted, so a seeded rng picks the same outcome every time
+                for (scenario, count) in scenarios.into_iter().sorted_by_key(|(s, _)| (s.attacker_losses, s.defender_losses)) {
+                    let mut new_state = *self;
+                    let attacker_move = if new_state.check_capture(*to, *attacking, scenario.defender_losses)? {
+                        *attacking
                     } else {
-                        attacker_move = scenario.attacker_losses;
-                    }
+                        scenario.attacker_losses
+                    };
                     new_state.add_armies(*from, -(attacker_move as i16))?;
                     new_states.push(new_state, count);
                 }
@@ -252,12 +251,12 @@ impl GameState {
         };
     }
 
-    pub fn named_territories_iter<'a>(&'a self) -> impl Iterator<Item = NamedTerritoryState> {
+    pub fn named_territories_iter(&self) -> impl Iterator<Item = NamedTerritoryState<'_>> {
         self.territories.iter().enumerate().map(|(i, t)| NamedTerritoryState { territory: Territory::try_from(i as u8).unwrap(), state: t })
     }
 
-    pub fn territories_states_of_player(&self, player: Player) -> Vec<NamedTerritoryState> {
-        self.named_territories_iter().filter_map(|territory| if territory.state.player == player { Some(territory) } else { None }).collect()
+    pub fn territories_states_of_player(&self, player: Player) -> Vec<NamedTerritoryState<'_>> {
+        self.named_territories_iter().filter(|territory| territory.state.player == player).collect()
     }
 
     pub fn continents_for_player(&self, player: Player) -> Vec<Continent> {
@@ -287,6 +286,11 @@ impl GameState {
     pub fn territory_states(&self) -> &[TerritoryState] {
         &self.territories
     }
+
+    // For states that come from outside, e.g. over the network. No checks beyond the number of territories.
+    pub fn from_parts(current_player: Player, phase: GamePhase, territories: [TerritoryState; Territory::COUNT]) -> Self {
+        Self { current_player, phase, territories }
+    }
 }
 
 #[cfg(test)]
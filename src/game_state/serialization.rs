@@ -0,0 +1,48 @@
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use strum::EnumCount;
+
+use crate::player::Player;
+use crate::territories::Territory;
+
+use super::{GamePhase, GameState, TerritoryState};
+
+// serde only derives arrays up to 32 elements, so the territories go through a Vec
+#[derive(Serialize, Deserialize)]
+struct SerializedGameState {
+    current_player: Player,
+    phase: GamePhase,
+    territories: Vec<TerritoryState>,
+}
+
+impl Serialize for GameState {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializedGameState { current_player: self.current_player, phase: self.phase, territories: self.territories.to_vec() }.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for GameState {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let state = SerializedGameState::deserialize(deserializer)?;
+        let count = state.territories.len();
+        let territories = state.territories.try_into().map_err(|_| D::Error::custom(format!("Expected {} territories, found {}", Territory::COUNT, count)))?;
+        Ok(GameState { current_player: state.current_player, phase: state.phase, territories })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game_state::{GameState, GameStateDuringInitialPlacement};
+
+    #[test]
+    fn roundtrip() {
+        let state = GameStateDuringInitialPlacement::new().place_random().start();
+        let json = serde_json::to_string(&state).unwrap();
+        let parsed: GameState = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.current_player(), state.current_player());
+        assert_eq!(parsed.phase(), state.phase());
+        assert_eq!(parsed.to_string(), state.to_string());
+
+        assert!(serde_json::from_str::<GameState>(r#"{"current_player":"A","phase":"Attack","territories":[]}"#).is_err());
+    }
+}
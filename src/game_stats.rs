@@ -0,0 +1,309 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use strum::{EnumCount, IntoEnumIterator};
+
+use crate::game_state::{GameState, Move};
+use crate::player::Player;
+use crate::territories::{Continent, Territory};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlayerStats {
+    pub reinforce_moves: u32,
+    pub attack_moves: u32,
+    pub fortify_moves: u32,
+    pub passes: u32,
+    pub attacks_won: u32,
+    pub armies_lost: u32,
+    pub largest_stack: u8,
+    // Round in which the player held the whole continent for the first time
+    pub continents_completed: BTreeMap<Continent, u16>,
+    // Thinking time of the bot in microseconds, not available for games rebuilt from a record
+    pub think_time_us: u64,
+    pub max_move_time_us: u64,
+    pub timeouts: u32,
+}
+
+impl PlayerStats {
+    pub fn moves(&self) -> u32 {
+        self.reinforce_moves + self.attack_moves + self.fortify_moves + self.passes
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameStats {
+    pub rounds: u16,
+    pub players: [PlayerStats; Player::COUNT],
+    // Territories and continents of both players at the end of every round, starting with the initial placement
+    pub territories: Vec<[u8; Player::COUNT]>,
+    pub continents: Vec<[u8; Player::COUNT]>,
+    // Seed of the dice, not available for games rebuilt from a record
+    #[serde(default)]
+    pub seed: Option<u64>,
+}
+
+// Called for every move, so these only look at the territories and do not allocate
+fn armies_on(state: &GameState, territories: [Territory; 2], player: Player) -> u32 {
+    territories.iter().map(|&t| state.territory_state(t)).filter(|t| t.player() == player).map(|t| t.armies() as u32).sum()
+}
+
+fn holds_continent(state: &GameState, player: Player, continent: Continent) -> bool {
+    Territory::iter().filter(|t| t.continent() == continent).all(|t| state.territory_state(t).player() == player)
+}
+
+impl GameStats {
+    pub fn new(initial_state: &GameState) -> Self {
+        let mut stats = GameStats { rounds: 0, players: Default::default(), territories: vec![], continents: vec![], seed: None };
+        for p in Player::iter() {
+            for continent in Continent::iter().filter(|&c| holds_continent(initial_state, p, c)) {
+                stats.players[p as usize].continents_completed.insert(continent, 0);
+            }
+        }
+        stats.record_largest_stacks(initial_state);
+        stats.record_round(0, initial_state);
+        stats
+    }
+
+    pub fn record_move(&mut self, round: u16, player: Player, before: &GameState, move_played: &Move, after: &GameState) {
+        let stats = &mut self.players[player as usize];
+        match move_played {
+            Move::Pass => stats.passes += 1,
+            Move::Reinforce { .. } => stats.reinforce_moves += 1,
+            Move::Fortify { .. } => stats.fortify_moves += 1,
+            Move::Attack { from, to, .. } => {
+                stats.attack_moves += 1;
+                // Only the two territories of the attack change
+                for p in Player::iter() {
+                    self.players[p as usize].armies_lost += armies_on(before, [*from, *to], p).saturating_sub(armies_on(after, [*from, *to], p));
+                }
+                // Continents only change hands by conquering a territory
+                if after.territory_state(*to).player() == player {
+                    let stats = &mut self.players[player as usize];
+                    stats.attacks_won += 1;
+                    let continent = to.continent();
+                    if !stats.continents_completed.contains_key(&continent) && holds_continent(after, player, continent) {
+                        stats.continents_completed.insert(continent, round);
+                    }
+                }
+            }
+        }
+        self.record_largest_stacks(after);
+    }
+
+    fn record_largest_stacks(&mut self, state: &GameState) {
+        for t in state.territory_states() {
+            let stats = &mut self.players[t.player() as usize];
+            stats.largest_stack = stats.largest_stack.max(t.armies());
+        }
+    }
+
+    pub fn record_time(&mut self, player: Player, elapsed: Duration, timed_out: bool) {
+        let stats = &mut self.players[player as usize];
+        let elapsed = elapsed.as_micros() as u64;
+        stats.think_time_us += elapsed;
+        stats.max_move_time_us = stats.max_move_time_us.max(elapsed);
+        stats.timeouts += timed_out as u32;
+    }
+
+    pub fn record_round(&mut self, round: u16, state: &GameState) {
+        self.rounds = round;
+        let mut territories = [0; Player::COUNT];
+        let mut continents = [0; Player::COUNT];
+        for t in state.territory_states() {
+            territories[t.player() as usize] += 1;
+        }
+        for p in Player::iter() {
+            continents[p as usize] = Continent::iter().filter(|&c| holds_continent(state, p, c)).count() as u8;
+        }
+        self.territories.push(territories);
+        self.continents.push(continents);
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AggregatePlayerStats {
+    pub avg_reinforce_moves: f64,
+    pub avg_attack_moves: f64,
+    pub avg_fortify_moves: f64,
+    pub avg_passes: f64,
+    pub attack_success_rate: f64,
+    pub avg_armies_lost: f64,
+    pub avg_largest_stack: f64,
+    pub avg_move_time_ms: f64,
+    pub max_move_time_ms: f64,
+    pub avg_timeouts: f64,
+    // Share of games in which the continent was completed, and the average round of the first completion
+    pub continent_completion_rate: BTreeMap<Continent, f64>,
+    pub avg_continent_completion_round: BTreeMap<Continent, f64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AggregateStats {
+    pub games: u32,
+    pub avg_rounds: f64,
+    pub players: [AggregatePlayerStats; Player::COUNT],
+    // Averaged over all games that lasted at least that many rounds
+    pub avg_territories: Vec<[f64; Player::COUNT]>,
+    pub avg_continents: Vec<[f64; Player::COUNT]>,
+}
+
+impl AggregateStats {
+    pub fn from_games<'a, I>(games: I) -> Self
+    where
+    I: IntoIterator<Item = &'a GameStats> {
+        let games = games.into_iter().collect::<Vec<_>>();
+        let mut result = AggregateStats { games: games.len() as u32, ..Default::default() };
+        if games.is_empty() {
+            return result;
+        }
+        let count = games.len() as f64;
+        result.avg_rounds = games.iter().map(|g| g.rounds as f64).sum::<f64>() / count;
+
+        for p in Player::iter() {
+            let players = games.iter().map(|g| &g.players[p as usize]).collect::<Vec<_>>();
+            let average = |f: &dyn Fn(&PlayerStats) -> f64| players.iter().map(|s| f(s)).sum::<f64>() / count;
+            let attacks = players.iter().map(|s| s.attack_moves).sum::<u32>();
+            let moves = players.iter().map(|s| s.moves()).sum::<u32>();
+            let think_time_ms = players.iter().map(|s| s.think_time_us).sum::<u64>() as f64 / 1000.0;
+            let aggregate = &mut result.players[p as usize];
+            aggregate.avg_reinforce_moves = average(&|s| s.reinforce_moves as f64);
+            aggregate.avg_attack_moves = average(&|s| s.attack_moves as f64);
+            aggregate.avg_fortify_moves = average(&|s| s.fortify_moves as f64);
+            aggregate.avg_passes = average(&|s| s.passes as f64);
+            aggregate.avg_armies_lost = average(&|s| s.armies_lost as f64);
+            aggregate.avg_largest_stack = average(&|s| s.largest_stack as f64);
+            aggregate.avg_move_time_ms = if moves == 0 { 0.0 } else { think_time_ms / moves as f64 };
+            aggregate.max_move_time_ms = players.iter().map(|s| s.max_move_time_us).max().unwrap_or(0) as f64 / 1000.0;
+            aggregate.avg_timeouts = average(&|s| s.timeouts as f64);
+            aggregate.attack_success_rate = if attacks == 0 { 0.0 } else { players.iter().map(|s| s.attacks_won).sum::<u32>() as f64 / attacks as f64 };
+
+            for continent in Continent::iter() {
+                let rounds = players.iter().filter_map(|s| s.continents_completed.get(&continent)).map(|&r| r as f64).collect::<Vec<_>>();
+                aggregate.continent_completion_rate.insert(continent, rounds.len() as f64 / count);
+                if !rounds.is_empty() {
+                    aggregate.avg_continent_completion_round.insert(continent, rounds.iter().sum::<f64>() / rounds.len() as f64);
+                }
+            }
+        }
+
+        let longest = games.iter().map(|g| g.territories.len()).max().unwrap_or(0);
+        for round in 0..longest {
+            let reached = games.iter().filter(|g| g.territories.len() > round).collect::<Vec<_>>();
+            let mut territories = [0.0; Player::COUNT];
+            let mut continents = [0.0; Player::COUNT];
+            for p in 0..Player::COUNT {
+                territories[p] = reached.iter().map(|g| g.territories[round][p] as f64).sum::<f64>() / reached.len() as f64;
+                continents[p] = reached.iter().map(|g| g.continents[round][p] as f64).sum::<f64>() / reached.len() as f64;
+            }
+            result.avg_territories.push(territories);
+            result.avg_continents.push(continents);
+        }
+
+        result
+    }
+
+    pub fn write_json<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(&mut writer, self)?;
+        writer.flush()
+    }
+}
+
+// One JSON object per game and line
+pub fn write_jsonl<'a, P, I>(path: P, games: I) -> io::Result<()>
+where
+P: AsRef<Path>,
+I: IntoIterator<Item = &'a GameStats> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    for game in games {
+        serde_json::to_writer(&mut writer, game)?;
+        writeln!(writer)?;
+    }
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use strum::{EnumCount, IntoEnumIterator};
+
+    use crate::bots::random_bot::RandomBot;
+    use crate::game::{self, Game, PlayOptions};
+    use crate::game_state::{GamePhase, GameState, Move, TerritoryState};
+    use crate::player::Player;
+    use crate::territories::{Continent, Territory};
+
+    use super::{AggregateStats, GameStats};
+
+    #[test]
+    fn stats_of_random_games() {
+        let mut games = vec![];
+        for _ in 0..5 {
+            let mut game = Game::new(RandomBot {}, RandomBot {});
+            let (rounds, _) = game.play_until_end(&game::evaluate_win, &PlayOptions::default()).unwrap();
+            let stats = game.stats().clone();
+
+            assert_eq!(stats.rounds, rounds);
+            assert_eq!(stats.territories.len(), rounds as usize + 1);
+            for territories in &stats.territories {
+                assert_eq!(territories.iter().sum::<u8>(), 42);
+            }
+            for player in &stats.players {
+                assert!(player.attacks_won <= player.attack_moves);
+                assert!(player.largest_stack > 0);
+            }
+            assert!(stats.players[Player::A as usize].reinforce_moves > 0);
+            games.push(stats);
+        }
+
+        let aggregate = AggregateStats::from_games(&games);
+        assert_eq!(aggregate.games, 5);
+        assert_eq!(aggregate.avg_territories[0].iter().sum::<f64>(), 42.0);
+        assert!(aggregate.players[0].attack_success_rate <= 1.0);
+    }
+
+    #[test]
+    fn continent_completed_by_conquest() {
+        let mut territories = [TerritoryState::new(Player::B, 1); Territory::COUNT];
+        for territory in Territory::iter().filter(|t| t.continent() == Continent::Oceania) {
+            territories[territory as usize] = TerritoryState::new(Player::A, 3);
+        }
+        territories[Territory::Siam as usize] = TerritoryState::new(Player::B, 2);
+        let before = GameState::from_parts(Player::A, GamePhase::Attack, territories);
+        territories[Territory::Siam as usize] = TerritoryState::new(Player::A, 1);
+        territories[Territory::Indonesia as usize] = TerritoryState::new(Player::A, 1);
+        let after = GameState::from_parts(Player::A, GamePhase::Attack, territories);
+
+        let mut stats = GameStats::new(&before);
+        assert_eq!(stats.players[Player::A as usize].continents_completed.get(&Continent::Oceania), Some(&0));
+        stats.record_move(3, Player::A, &before, &Move::Attack { from: Territory::Indonesia, to: Territory::Siam, attacking: 2 }, &after);
+        let player_a = &stats.players[Player::A as usize];
+        assert_eq!(player_a.attacks_won, 1);
+        assert_eq!(player_a.armies_lost, 1);
+        assert_eq!(stats.players[Player::B as usize].armies_lost, 2);
+        assert_eq!(player_a.largest_stack, 3);
+        assert_eq!(player_a.continents_completed.get(&Continent::Asia), None);
+
+        // Conquering the last territory of a continent completes it
+        let mut territories = [TerritoryState::new(Player::A, 1); Territory::COUNT];
+        territories[Territory::Japan as usize] = TerritoryState::new(Player::B, 4);
+        let before = GameState::from_parts(Player::A, GamePhase::Attack, territories);
+        territories[Territory::Japan as usize] = TerritoryState::new(Player::A, 1);
+        let after = GameState::from_parts(Player::A, GamePhase::Attack, territories);
+        let mut stats = GameStats::new(&before);
+        stats.record_move(5, Player::A, &before, &Move::Attack { from: Territory::Kamchatka, to: Territory::Japan, attacking: 1 }, &after);
+        let player_a = &stats.players[Player::A as usize];
+        assert_eq!(player_a.continents_completed.get(&Continent::Asia), Some(&5));
+        assert_eq!(player_a.continents_completed.get(&Continent::Europe), Some(&0));
+        assert_eq!(stats.players[Player::B as usize].largest_stack, 4);
+
+        let json = serde_json::to_string(&stats).unwrap();
+        assert!(json.contains("\"Asia\":5"));
+        let parsed: GameStats = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.players[Player::A as usize].continents_completed, player_a.continents_completed);
+    }
+}
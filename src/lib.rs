@@ -0,0 +1,38 @@
+// The game engine, bots and tools around them. The `risk` binary in src/bin/risk (the command line, training and
+// the terminal viewer) is built on top of this. The types most users need are re-exported here, the modules hold the rest.
+
+pub mod territories;
+pub mod player;
+pub mod game_state;
+pub mod bots;
+pub mod game;
+pub mod game_stats;
+pub mod game_record;
+pub mod arena;
+#[cfg(feature = "training")]
+pub mod evolver;
+pub mod stats;
+pub mod tournament;
+pub mod env;
+pub mod adjudicator;
+pub mod protocol;
+pub mod server;
+
+pub use adjudicator::Adjudicator;
+pub use arena::{ArenaOptions, ArenaResult};
+pub use bots::{Bot, BotError, BotRegistry, MoveContext};
+pub use env::RiskEnv;
+#[cfg(feature = "training")]
+pub use evolver::{Evaluator, Evolver, Mutator};
+pub use game::{Game, GameConfig, GameError, GameResult, TimeControl};
+pub use game_record::GameRecord;
+#[cfg(feature = "render")]
+pub use game_state::{DrawMapError, DrawMapOptions};
+pub use game_state::{ApplyMoveResult, GamePhase, GameState, GameStateDuringInitialPlacement, Move, MoveApplyErr, TerritoryState};
+pub use player::Player;
+pub use territories::{Continent, Territory};
+
+#[cfg(feature = "python")]
+mod python;
+#[cfg(feature = "ffi")]
+pub mod ffi;
@@ -1,7 +1,8 @@
+use serde::{Deserialize, Serialize};
 use strum_macros::{EnumIter, EnumCount};
 use std::fmt::{self, Display, Formatter};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, EnumCount)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, EnumCount, Serialize, Deserialize)]
 pub enum Player {
     A,
     B,
@@ -0,0 +1,195 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use strum::EnumCount;
+
+use crate::bots::MoveContext;
+use crate::game::{GameConfig, GameResult};
+use crate::game_state::{GamePhase, GameState, Move, TerritoryState};
+use crate::player::Player;
+use crate::territories::Territory;
+
+// Version of the line protocol spoken with external bots and network clients, see PROTOCOL.md
+pub const PROTOCOL_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    Reinforce,
+    Attack,
+    Fortify,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TerritoryInfo {
+    pub territory: Territory,
+    pub player: Player,
+    pub armies: u8,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BoardState {
+    pub player: Player,
+    pub phase: Phase,
+    // Armies left to place, 0 outside the reinforce phase
+    pub reinforcements: u8,
+    pub territories: Vec<TerritoryInfo>,
+}
+
+impl BoardState {
+    pub fn new(game_state: &GameState) -> Self {
+        let (phase, reinforcements) = match game_state.phase() {
+            GamePhase::Reinforce(armies) => (Phase::Reinforce, armies),
+            GamePhase::Attack => (Phase::Attack, 0),
+            GamePhase::Fortify => (Phase::Fortify, 0),
+        };
+        Self {
+            player: game_state.current_player(),
+            phase,
+            reinforcements,
+            territories: game_state.named_territories_iter()
+                .map(|t| TerritoryInfo { territory: t.territory(), player: t.state().player(), armies: t.state().armies() })
+                .collect(),
+        }
+    }
+
+    pub fn game_state(&self) -> Result<GameState, String> {
+        let mut territories = [TerritoryState::new(Player::A, 0); Territory::COUNT];
+        let mut seen = [false; Territory::COUNT];
+        for info in &self.territories {
+            territories[info.territory as usize] = TerritoryState::new(info.player, info.armies);
+            seen[info.territory as usize] = true;
+        }
+        if seen.iter().any(|seen| !seen) {
+            return Err(format!("Expected all {} territories in the state", Territory::COUNT));
+        }
+        let phase = match self.phase {
+            Phase::Reinforce => GamePhase::Reinforce(self.reinforcements),
+            Phase::Attack => GamePhase::Attack,
+            Phase::Fortify => GamePhase::Fortify,
+        };
+        Ok(GameState::from_parts(self.player, phase, territories))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MoveRequest {
+    #[serde(flatten)]
+    pub state: BoardState,
+    pub legal_moves: Vec<Move>,
+    // Only sent with time control, the move has to arrive within this time
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_for_move_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clock_ms: Option<u64>,
+    // Only sent in network games, the answer echoes it so late answers to earlier requests can be told apart
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<u64>,
+}
+
+impl MoveRequest {
+    pub fn new(game_state: &GameState, context: &MoveContext) -> Self {
+        Self {
+            state: BoardState::new(game_state),
+            legal_moves: game_state.legal_moves(),
+            time_for_move_ms: context.time_for_move.map(|t| t.as_millis() as u64),
+            clock_ms: context.clock.map(|t| t.as_millis() as u64),
+            id: None,
+        }
+    }
+
+    pub fn context(&self) -> MoveContext {
+        MoveContext { time_for_move: self.time_for_move_ms.map(Duration::from_millis), clock: self.clock_ms.map(Duration::from_millis) }
+    }
+}
+
+// Messages sent to the bot, one JSON object per line. Only `move` expects an answer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Request {
+    Hello { protocol: u32 },
+    // Before the first move, `opponent` is only known in network games
+    NewGame {
+        player: Player,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        opponent: Option<String>,
+        config: GameConfig,
+    },
+    Move(MoveRequest),
+    // Sent to both players after every move, `state` includes the outcome of the dice
+    Update { player: Player, move_played: Move, state: BoardState },
+    // `winner` is None for a draw, `reason` is set when a player forfeited
+    GameOver { winner: Option<Player>, reason: Option<String> },
+}
+
+impl Request {
+    pub fn to_line(&self) -> String {
+        let mut line = serde_json::to_string(self).expect("Requests always serialize");
+        line.push('\n');
+        line
+    }
+
+    pub fn game_over(result: GameResult, reason: Option<String>) -> Self {
+        let winner = match result {
+            GameResult::Win(player) => Some(player),
+            GameResult::Draw => None,
+        };
+        Request::GameOver { winner, reason }
+    }
+}
+
+// Messages from network clients besides their moves
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    Join { name: String },
+}
+
+// The answer to a move request is the move itself, e.g. {"type":"attack","from":"Alaska","to":"Kamchatka","attacking":3}
+pub fn parse_move(line: &str) -> Result<Move, String> {
+    serde_json::from_str(line.trim()).map_err(|e| format!("Invalid move '{}': {}", line.trim(), e))
+}
+
+// Network clients may add the `id` of the request to the move, e.g. {"type":"pass","id":3}
+pub fn parse_answer(line: &str) -> Result<(Option<u64>, Move), String> {
+    let invalid = |e: serde_json::Error| format!("Invalid move '{}': {}", line.trim(), e);
+    let mut answer: serde_json::Value = serde_json::from_str(line.trim()).map_err(invalid)?;
+    let id = answer.as_object_mut().and_then(|answer| answer.remove("id"));
+    let id = match id {
+        None => None,
+        Some(id) => Some(id.as_u64().ok_or_else(|| format!("Invalid id in '{}'", line.trim()))?),
+    };
+    Ok((id, serde_json::from_value(answer).map_err(invalid)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bots::MoveContext;
+    use crate::game_state::{GameStateDuringInitialPlacement, Move};
+    use crate::territories::Territory;
+
+    use super::{parse_answer, parse_move, MoveRequest, Request};
+
+    #[test]
+    fn request_and_answer() {
+        let state = GameStateDuringInitialPlacement::new().place_random().start();
+        let line = Request::Move(MoveRequest::new(&state, &MoveContext::default())).to_line();
+        assert!(line.ends_with('\n') && !line.trim_end().contains('\n'));
+        assert!(line.starts_with(r#"{"type":"move","player":"#));
+        assert!(line.contains(r#""phase":"reinforce""#));
+        assert!(line.contains(r#"{"territory":"Alaska","player":"#));
+
+        let Ok(Request::Move(request)) = serde_json::from_str(&line) else { panic!("Could not parse {}", line) };
+        assert_eq!(request.legal_moves, state.legal_moves());
+        assert_eq!(request.state.game_state().unwrap().legal_moves(), state.legal_moves());
+        assert!(!line.contains("time_for_move_ms") && request.context() == MoveContext::default());
+
+        assert_eq!(parse_move(r#"{"type":"pass"}"#), Ok(Move::Pass));
+        assert_eq!(parse_move(r#" {"type":"reinforce","territory":"NorthwestTerritory","armies":2}"#), Ok(Move::Reinforce { territory: Territory::NorthwestTerritory, armies: 2 }));
+        assert!(parse_move(r#"{"type":"reinforce","territory":"Atlantis","armies":2}"#).is_err());
+
+        assert_eq!(parse_answer(r#"{"type":"pass"}"#), Ok((None, Move::Pass)));
+        assert_eq!(parse_answer(r#"{"type":"pass","id":7}"#), Ok((Some(7), Move::Pass)));
+        assert!(parse_answer(r#"{"type":"pass","id":"seven"}"#).is_err());
+    }
+}
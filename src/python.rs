@@ -0,0 +1,248 @@
+use numpy::{PyArray1, PyReadonlyArray1};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use strum::{EnumCount, IntoEnumIterator};
+
+use crate::adjudicator::Adjudicator;
+use crate::arena;
+use crate::bots::neural_bot::NeuralBot;
+use crate::bots::registry::BotRegistry;
+use crate::bots::{Bot, Float};
+use crate::env::{self, Reward, RiskEnv};
+use crate::game::{GameConfig, GameResult};
+use crate::game_state::{GamePhase, GameState, GameStateDuringInitialPlacement, Move, TerritoryState};
+use crate::player::Player;
+use crate::territories::Territory;
+
+// Python bindings, see pyproject.toml and python/tests for how they are used. Territories are their index in
+// `Territory`, players are 0 and 1, moves are the JSON of the protocol, e.g. {"type":"attack","from":"Alaska",...}
+
+fn value_error(err: impl ToString) -> PyErr {
+    PyValueError::new_err(err.to_string())
+}
+
+fn player(index: u8) -> PyResult<Player> {
+    match index {
+        0 => Ok(Player::A),
+        1 => Ok(Player::B),
+        _ => Err(value_error(format!("Invalid player {}, expected 0 or 1", index))),
+    }
+}
+
+fn result_winner(result: GameResult) -> Option<u8> {
+    match result {
+        GameResult::Win(player) => Some(player as u8),
+        GameResult::Draw => None,
+    }
+}
+
+#[pyclass(name = "Move", eq, frozen)]
+#[derive(Clone, Copy, PartialEq)]
+struct PyMove(Move);
+
+#[pymethods]
+impl PyMove {
+    #[staticmethod]
+    fn from_json(json: &str) -> PyResult<Self> {
+        serde_json::from_str(json).map(PyMove).map_err(value_error)
+    }
+
+    #[pyo3(name = "to_json")]
+    fn json(&self) -> String {
+        serde_json::to_string(&self.0).unwrap()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", self.0)
+    }
+}
+
+#[pyclass(name = "GameState", frozen)]
+#[derive(Clone, Copy)]
+struct PyGameState(GameState);
+
+#[pymethods]
+impl PyGameState {
+    // A random start, the same for the same seed
+    #[new]
+    #[pyo3(signature = (seed=None))]
+    fn new(seed: Option<u64>) -> Self {
+        let mut rng = seed.map_or_else(StdRng::from_entropy, StdRng::seed_from_u64);
+        PyGameState(GameStateDuringInitialPlacement::new().place_random_with(&mut rng).start())
+    }
+
+    // `territories` are (player, armies) in the order of the territories, `reinforcements` is only used in that phase
+    #[staticmethod]
+    #[pyo3(signature = (current_player, phase, territories, reinforcements=0))]
+    fn from_parts(current_player: u8, phase: &str, territories: Vec<(u8, u8)>, reinforcements: u8) -> PyResult<Self> {
+        let phase = match phase {
+            "reinforce" => GamePhase::Reinforce(reinforcements),
+            "attack" => GamePhase::Attack,
+            "fortify" => GamePhase::Fortify,
+            _ => return Err(value_error(format!("Unknown phase '{}', expected reinforce, attack or fortify", phase))),
+        };
+        let territories = territories.into_iter().map(|(p, armies)| Ok(TerritoryState::new(player(p)?, armies))).collect::<PyResult<Vec<_>>>()?;
+        let territories = territories.try_into().map_err(|t: Vec<_>| value_error(format!("Expected {} territories, found {}", Territory::COUNT, t.len())))?;
+        Ok(PyGameState(GameState::from_parts(player(current_player)?, phase, territories)))
+    }
+
+    #[getter]
+    fn current_player(&self) -> u8 {
+        self.0.current_player() as u8
+    }
+
+    #[getter]
+    fn phase(&self) -> &'static str {
+        match self.0.phase() {
+            GamePhase::Reinforce(_) => "reinforce",
+            GamePhase::Attack => "attack",
+            GamePhase::Fortify => "fortify",
+        }
+    }
+
+    #[getter]
+    fn territories(&self) -> Vec<(u8, u8)> {
+        self.0.territory_states().iter().map(|t| (t.player() as u8, t.armies())).collect()
+    }
+
+    fn is_finished(&self) -> bool {
+        self.0.is_finished()
+    }
+
+    fn legal_moves(&self) -> Vec<PyMove> {
+        self.0.legal_moves().into_iter().map(PyMove).collect()
+    }
+
+    // Every outcome of the move with its probability, attacks have one per way the dice can fall
+    fn apply_move(&self, move_to_play: PyMove) -> PyResult<Vec<(PyGameState, f64)>> {
+        let result = self.0.apply_move(&move_to_play.0).map_err(|err| value_error(format!("{:?}", err)))?;
+        let total = result.total_count() as f64;
+        Ok(result.states_with_count().iter().map(|s| (PyGameState(*s.state()), s.count() as f64 / total)).collect())
+    }
+
+    // One outcome picked by its probability
+    fn sample_move(&self, move_to_play: PyMove) -> PyResult<PyGameState> {
+        let result = self.0.apply_move(&move_to_play.0).map_err(|err| value_error(format!("{:?}", err)))?;
+        Ok(PyGameState(result.random_state_by_probability()))
+    }
+
+    // Own and enemy armies of every territory seen from `player`, the current one by default, then the phase
+    #[pyo3(signature = (player=None))]
+    fn features<'py>(&self, py: Python<'py>, player: Option<u8>) -> PyResult<Bound<'py, PyArray1<Float>>> {
+        let player = player.map_or(Ok(self.0.current_player()), self::player)?;
+        Ok(PyArray1::from_vec(py, env::observation(&self.0, player)))
+    }
+
+    fn __repr__(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+// Any bot of the registry, e.g. Bot("rule_based") or Bot("neural:weights/island_0.bin")
+#[pyclass(name = "Bot", unsendable)]
+struct PyBot(Box<dyn Bot>);
+
+#[pymethods]
+impl PyBot {
+    #[new]
+    fn new(spec: &str) -> PyResult<Self> {
+        BotRegistry::default().create(spec).map(PyBot).map_err(value_error)
+    }
+
+    fn make_move(&mut self, state: &PyGameState) -> PyMove {
+        PyMove(self.0.make_move(state.0))
+    }
+}
+
+type Step<'py> = (Bound<'py, PyArray1<Float>>, Float, bool, Bound<'py, PyDict>);
+
+// `RiskEnv` against a bot of the registry, observations and masks are numpy arrays
+#[pyclass(name = "Env", unsendable)]
+struct PyEnv(RiskEnv<Box<dyn Bot>>);
+
+#[pymethods]
+impl PyEnv {
+    #[classattr]
+    const ACTIONS: usize = env::ACTIONS;
+    #[classattr]
+    const OBSERVATION: usize = env::OBSERVATION;
+
+    #[new]
+    #[pyo3(signature = (opponent="random", agent=0, max_rounds=200, adjudicator="draw", win=1.0, territories=0.0, income=0.0))]
+    fn new(opponent: &str, agent: u8, max_rounds: u16, adjudicator: &str, win: Float, territories: Float, income: Float) -> PyResult<Self> {
+        let opponent = BotRegistry::default().create(opponent).map_err(value_error)?;
+        let adjudicator = adjudicator.parse::<Adjudicator>().map_err(value_error)?;
+        let config = GameConfig { max_rounds, ..GameConfig::default() };
+        Ok(PyEnv(RiskEnv::new(opponent).agent(player(agent)?).config(config).adjudicator(adjudicator).reward(Reward { win, territories, income })))
+    }
+
+    #[pyo3(signature = (seed=None))]
+    fn reset<'py>(&mut self, py: Python<'py>, seed: Option<u64>) -> Bound<'py, PyArray1<Float>> {
+        PyArray1::from_vec(py, self.0.reset(seed).0)
+    }
+
+    // (observation, reward, done, info) where info has the round, the winner once the game is over and a forfeit reason
+    fn step<'py>(&mut self, py: Python<'py>, action: usize) -> PyResult<Step<'py>> {
+        let (observation, reward, done, info) = self.0.step(action).map_err(value_error)?;
+        let dict = PyDict::new(py);
+        dict.set_item("round", info.round)?;
+        dict.set_item("winner", info.result.and_then(result_winner))?;
+        dict.set_item("forfeit", info.forfeit)?;
+        dict.set_item("opponent_moves", info.opponent_moves.into_iter().map(PyMove).collect::<Vec<_>>())?;
+        Ok((PyArray1::from_vec(py, observation), reward, done, dict))
+    }
+
+    fn action_mask<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<bool>> {
+        PyArray1::from_vec(py, self.0.action_mask())
+    }
+
+    fn action_move(&self, action: usize) -> Option<PyMove> {
+        self.0.action_move(action).map(PyMove)
+    }
+
+    fn move_action(&self, move_to_play: PyMove) -> Option<usize> {
+        self.0.move_action(&move_to_play.0)
+    }
+
+    #[getter]
+    fn state(&self) -> PyGameState {
+        PyGameState(*self.0.state())
+    }
+}
+
+// Plays `games` games between two bots of the registry on all cores, the GIL is released meanwhile
+#[pyfunction]
+#[pyo3(signature = (games, bot_a, bot_b, adjudicator="draw"))]
+fn play_games(py: Python<'_>, games: u32, bot_a: &str, bot_b: &str, adjudicator: &str) -> PyResult<(u32, u32, u32)> {
+    let registry = BotRegistry::default();
+    let (factory_a, factory_b) = (registry.factory(bot_a).map_err(value_error)?, registry.factory(bot_b).map_err(value_error)?);
+    let adjudicator = adjudicator.parse::<Adjudicator>().map_err(value_error)?;
+    let result = py.detach(|| arena::play_games(games, move |state| adjudicator.adjudicate(state), &factory_a, &factory_b)).map_err(value_error)?;
+    Ok((result.wins(Player::A), result.wins(Player::B), result.draws()))
+}
+
+// Writes weights trained in Python so `Bot("neural:<path>")` and the CLI can load them
+#[pyfunction]
+fn save_neural_weights(weights: PyReadonlyArray1<Float>, path: &str) -> PyResult<()> {
+    let weights = weights.as_slice().map_err(value_error)?;
+    if weights.len() != NeuralBot::LENGTH {
+        return Err(value_error(format!("Expected {} weights, found {}", NeuralBot::LENGTH, weights.len())));
+    }
+    NeuralBot::save_weights_and_biases(weights, path).map_err(value_error)
+}
+
+#[pymodule]
+fn risk(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyMove>()?;
+    m.add_class::<PyGameState>()?;
+    m.add_class::<PyBot>()?;
+    m.add_class::<PyEnv>()?;
+    m.add_function(wrap_pyfunction!(play_games, m)?)?;
+    m.add_function(wrap_pyfunction!(save_neural_weights, m)?)?;
+    m.add("NEURAL_WEIGHTS", NeuralBot::LENGTH)?;
+    m.add("TERRITORIES", Territory::iter().map(|t| t.to_string()).collect::<Vec<_>>())?;
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+
+use crate::bots::Bot;
+use crate::game::GameResult;
+use crate::player::Player;
+use crate::protocol::{ClientMessage, Request};
+
+// Joins a game on a server and lets `bot` play it, including its hooks. Every message from the server is passed to
+// `observe` first.
+// Returns the seat that was played and the result.
+pub fn play<A: ToSocketAddrs, B: Bot>(address: A, name: &str, mut bot: B, mut observe: impl FnMut(&Request)) -> Result<(Player, GameResult), String> {
+    let stream = TcpStream::connect(address).map_err(|e| format!("Could not connect: {}", e))?;
+    stream.set_nodelay(true).map_err(|e| e.to_string())?;
+    let mut writer = stream.try_clone().map_err(|e| e.to_string())?;
+    let mut send = |line: String| writeln!(writer, "{}", line).and_then(|_| writer.flush()).map_err(|e| format!("Could not send: {}", e));
+    send(serde_json::to_string(&ClientMessage::Join { name: name.to_string() }).unwrap())?;
+
+    let mut seat = None;
+    for line in BufReader::new(stream).lines() {
+        let line = line.map_err(|e| format!("Connection lost: {}", e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let request: Request = serde_json::from_str(&line).map_err(|e| format!("Invalid message '{}': {}", line, e))?;
+        observe(&request);
+
+        match request {
+            Request::NewGame { player, config, .. } => {
+                seat = Some(player);
+                bot.new_game(player, &config);
+            }
+            Request::Move(request) => {
+                let state = request.state.game_state()?;
+                let move_to_play = bot.try_make_move(state, &request.context()).map_err(|e| e.to_string())?;
+                let mut answer = serde_json::to_value(move_to_play).unwrap();
+                if let Some(id) = request.id {
+                    answer["id"] = id.into();
+                }
+                send(answer.to_string())?;
+            }
+            Request::Update { player, move_played, state } => bot.observe(player, &move_played, &state.game_state()?),
+            Request::GameOver { winner, .. } => {
+                let seat = seat.ok_or("Game ended before a seat was assigned")?;
+                let result = winner.map_or(GameResult::Draw, GameResult::Win);
+                bot.game_over(result);
+                return Ok((seat, result));
+            }
+            Request::Hello { .. } => {}
+        }
+    }
+    Err("Server closed the connection".to_string())
+}
@@ -0,0 +1,222 @@
+use std::io::{self, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::Duration;
+
+use crate::adjudicator::Adjudicator;
+use crate::game::{Game, GameConfig, GameError, GameResult};
+use crate::protocol::Request;
+
+use remote_bot::RemoteBot;
+
+pub mod client;
+pub mod remote_bot;
+
+#[derive(Debug)]
+pub struct HostedGame {
+    pub players: [String; 2],
+    pub rounds: u16,
+    pub outcome: Result<GameResult, GameError>,
+}
+
+// Hosts games for clients that connect over TCP. Clients join with their name and are seated in the order they join,
+// then play with the messages in PROTOCOL.md.
+pub struct Server {
+    listener: TcpListener,
+    config: GameConfig,
+    adjudicator: Adjudicator,
+    timeout: Duration,
+    // Clients that finished joining on their own thread and are waiting for a game
+    joined: (Sender<RemoteBot>, Receiver<RemoteBot>),
+}
+
+impl Server {
+    pub fn bind<A: ToSocketAddrs>(address: A) -> io::Result<Self> {
+        // Polled between checks for joined clients, see `accept_player`
+        let listener = TcpListener::bind(address)?;
+        listener.set_nonblocking(true)?;
+        Ok(Self { listener, config: GameConfig::default(), adjudicator: Adjudicator::default(), timeout: Duration::from_secs(30), joined: channel() })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    pub fn config(mut self, config: GameConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn adjudicator(mut self, adjudicator: Adjudicator) -> Self {
+        self.adjudicator = adjudicator;
+        self
+    }
+
+    // Time a client has for joining and for every move
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    // Every connection joins on its own thread, so a client that is slow to join does not hold up the others.
+    // Connections that do not join properly are dropped.
+    fn accept_player(&self) -> io::Result<RemoteBot> {
+        let (sender, receiver) = &self.joined;
+        loop {
+            match self.listener.accept() {
+                Ok((stream, _)) => {
+                    // Accepted sockets inherit non-blocking mode on some platforms
+                    stream.set_nonblocking(false)?;
+                    let (sender, timeout) = (sender.clone(), self.timeout);
+                    thread::spawn(move || {
+                        if let Ok(player) = RemoteBot::accept(stream, timeout) {
+                            sender.send(player).ok();
+                        }
+                    });
+                }
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
+                Err(e) => return Err(e),
+            }
+            match receiver.recv_timeout(Duration::from_millis(10)) {
+                Ok(player) => return Ok(player),
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => unreachable!("The server keeps a sender"),
+            }
+        }
+    }
+
+    // Seats the next two clients that join and plays one game between them
+    pub fn host_game(&self) -> io::Result<HostedGame> {
+        let bot_a = self.accept_player()?;
+        let bot_b = self.accept_player()?;
+        play_hosted(bot_a, bot_b, self.config, self.adjudicator)
+    }
+
+    // Hosts `games` games, or until the listener fails if None. Every pair of clients plays on its own thread.
+    pub fn run(&self, games: Option<u32>, on_finished: impl Fn(io::Result<HostedGame>)) -> io::Result<()> {
+        let (sender, receiver) = channel();
+        let mut started = 0;
+        while games.is_none_or(|games| started < games) {
+            let bot_a = self.accept_player()?;
+            let bot_b = self.accept_player()?;
+            let sender = sender.clone();
+            let (config, adjudicator) = (self.config, self.adjudicator);
+            thread::spawn(move || sender.send(play_hosted(bot_a, bot_b, config, adjudicator)).ok());
+            started += 1;
+            receiver.try_iter().for_each(&on_finished);
+        }
+        drop(sender);
+        receiver.iter().for_each(on_finished);
+        Ok(())
+    }
+}
+
+// The bots tell their clients about the start of the game and every move, the end is sent here with the reason
+fn play_hosted(bot_a: RemoteBot, bot_b: RemoteBot, config: GameConfig, adjudicator: Adjudicator) -> io::Result<HostedGame> {
+    let players = [bot_a.name().to_string(), bot_b.name().to_string()];
+    let mut connections = [bot_a.connection()?, bot_b.connection()?];
+    let bot_a = bot_a.opponent(&players[1]);
+    let bot_b = bot_b.opponent(&players[0]);
+
+    let mut game = Game::new(bot_a, bot_b).with_config(config);
+    let evaluate = |state| adjudicator.adjudicate(state);
+    let outcome = loop {
+        if let Some(result) = game.check_result(&evaluate) {
+            break Ok(result);
+        }
+        if let Err(err) = game.play_move() {
+            break Err(err);
+        }
+    };
+
+    let game_over = match &outcome {
+        Ok(result) => Request::game_over(*result, None),
+        Err(err) => Request::game_over(GameResult::Win(err.player().next()), Some(err.to_string())),
+    };
+    broadcast(&mut connections, &game_over);
+    Ok(HostedGame { players, rounds: game.round(), outcome })
+}
+
+fn broadcast(connections: &mut [TcpStream], request: &Request) {
+    let line = request.to_line();
+    for connection in connections {
+        connection.write_all(line.as_bytes()).ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpStream;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    use crate::bots::random_bot::RandomBot;
+    use crate::game::{GameError, GameResult};
+    use crate::player::Player;
+    use crate::protocol::Request;
+
+    use super::{client, Server};
+
+    #[test]
+    fn loopback_game() {
+        let server = Server::bind("127.0.0.1:0").unwrap();
+        let address = server.local_addr().unwrap();
+        let host = thread::spawn(move || server.host_game().unwrap());
+
+        let clients = ["alice", "bob"].map(|name| thread::spawn(move || {
+            let mut updates = 0;
+            let result = client::play(address, name, RandomBot {}, |request| updates += matches!(request, Request::Update { .. }) as usize);
+            (result, updates)
+        }));
+        let hosted = host.join().unwrap();
+        let results = clients.map(|client| client.join().unwrap());
+
+        // Seats depend on which client connects first
+        let result = hosted.outcome.unwrap();
+        for (name, (client_result, _)) in ["alice", "bob"].iter().zip(&results) {
+            let (seat, client_result) = client_result.clone().unwrap();
+            assert_eq!(hosted.players[if seat == Player::A { 0 } else { 1 }], *name);
+            assert_eq!(client_result, result);
+        }
+        assert!(results[0].1 > 0 && results[0].1 == results[1].1);
+    }
+
+    #[test]
+    fn forfeit_on_garbage() {
+        let server = Server::bind("127.0.0.1:0").unwrap().timeout(Duration::from_secs(5));
+        let address = server.local_addr().unwrap();
+        let host = thread::spawn(move || server.host_game().unwrap());
+
+        let mut stream = TcpStream::connect(address).unwrap();
+        writeln!(stream, r#"{{"type":"join","name":"garbage"}}"#).unwrap();
+        let bot_b = thread::spawn(move || client::play(address, "random", RandomBot {}, |_| {}));
+
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut line = String::new();
+        while !line.contains(r#""type":"move""#) {
+            line.clear();
+            reader.read_line(&mut line).unwrap();
+        }
+        writeln!(stream, "resign").unwrap();
+
+        let hosted = host.join().unwrap();
+        assert!(matches!(hosted.outcome, Err(GameError::BotFailed { player: Player::A, .. })));
+        assert_eq!(bot_b.join().unwrap().unwrap(), (Player::B, GameResult::Win(Player::B)));
+    }
+
+    #[test]
+    fn silent_client_does_not_block_others() {
+        let server = Server::bind("127.0.0.1:0").unwrap().timeout(Duration::from_secs(30));
+        let address = server.local_addr().unwrap();
+        let _silent = TcpStream::connect(address).unwrap();
+        let mut joining = TcpStream::connect(address).unwrap();
+        writeln!(joining, r#"{{"type":"join","name":"quick"}}"#).unwrap();
+
+        let started = Instant::now();
+        let player = server.accept_player().unwrap();
+        assert_eq!(player.name(), "quick");
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+}
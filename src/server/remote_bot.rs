@@ -0,0 +1,176 @@
+use std::io::{self, BufRead, BufReader, ErrorKind, Write};
+use std::net::TcpStream;
+use std::time::{Duration, Instant};
+
+use crate::game::GameConfig;
+use crate::game_state::{GameState, Move};
+use crate::player::Player;
+use crate::protocol::{self, BoardState, ClientMessage, MoveRequest, Request, PROTOCOL_VERSION};
+use crate::bots::{Bot, BotError, MoveContext};
+
+// A player connected over TCP, it gets the same move requests as an external bot
+pub struct RemoteBot {
+    name: String,
+    opponent: Option<String>,
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+    timeout: Duration,
+    // Id of the last move request, and how many requests timed out without an answer yet
+    request_id: u64,
+    unanswered: u32,
+}
+
+impl RemoteBot {
+    // Greets a new connection and waits for it to join
+    pub fn accept(stream: TcpStream, timeout: Duration) -> Result<Self, String> {
+        // Messages are single lines that are answered right away, batching them only adds latency
+        stream.set_nodelay(true).and_then(|_| stream.set_read_timeout(Some(timeout))).map_err(|e| e.to_string())?;
+        let writer = stream.try_clone().map_err(|e| e.to_string())?;
+        let mut bot = Self { name: String::new(), opponent: None, reader: BufReader::new(stream), writer, timeout, request_id: 0, unanswered: 0 };
+        bot.send(&Request::Hello { protocol: PROTOCOL_VERSION }).map_err(|e| e.to_string())?;
+
+        let line = bot.read_line(timeout).map_err(|e| e.to_string())?;
+        match serde_json::from_str(&line) {
+            Ok(ClientMessage::Join { name }) => bot.name = name,
+            Err(e) => return Err(format!("Expected a join message, found '{}': {}", line, e)),
+        }
+        Ok(bot)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    // Name of the other player, sent to the client when the game starts
+    pub fn opponent(mut self, opponent: &str) -> Self {
+        self.opponent = Some(opponent.to_string());
+        self
+    }
+
+    // A second handle to the connection for broadcasting while the bot is owned by the game
+    pub fn connection(&self) -> io::Result<TcpStream> {
+        self.writer.try_clone()
+    }
+
+    pub fn send(&mut self, request: &Request) -> io::Result<()> {
+        self.writer.write_all(request.to_line().as_bytes())?;
+        self.writer.flush()
+    }
+
+    fn read_line(&mut self, timeout: Duration) -> Result<String, BotError> {
+        loop {
+            let mut line = String::new();
+            match self.reader.read_line(&mut line) {
+                Ok(0) => return Err(BotError::Crashed("Disconnected".to_string())),
+                Ok(_) if line.trim().is_empty() => continue,
+                Ok(_) => return Ok(line.trim().to_string()),
+                Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => return Err(BotError::Timeout(timeout)),
+                Err(e) => return Err(BotError::Crashed(e.to_string())),
+            }
+        }
+    }
+}
+
+impl Bot for RemoteBot {
+    fn make_move(&mut self, game_state: GameState) -> Move {
+        self.try_make_move(game_state, &MoveContext::default()).unwrap_or_else(|err| panic!("Remote bot {}", err))
+    }
+
+    fn try_make_move(&mut self, game_state: GameState, context: &MoveContext) -> Result<Move, BotError> {
+        let timeout = context.time_for_move.map_or(self.timeout, |time| time.min(self.timeout));
+        let deadline = Instant::now() + timeout;
+        self.request_id += 1;
+        let mut request = MoveRequest::new(&game_state, context);
+        request.id = Some(self.request_id);
+        self.send(&Request::Move(request)).map_err(|e| BotError::Crashed(e.to_string()))?;
+
+        loop {
+            // A zero timeout would mean blocking forever
+            let left = deadline.saturating_duration_since(Instant::now()).max(Duration::from_millis(1));
+            self.reader.get_ref().set_read_timeout(Some(left)).map_err(|e| BotError::Crashed(e.to_string()))?;
+            let line = match self.read_line(timeout) {
+                Err(BotError::Timeout(timeout)) => {
+                    self.unanswered += 1;
+                    return Err(BotError::Timeout(timeout));
+                }
+                line => line?,
+            };
+            // Answers to requests that timed out arrive late but in order, clients without ids are matched by counting
+            let (id, move_to_play) = protocol::parse_answer(&line).map_err(BotError::Protocol)?;
+            match id {
+                Some(id) if id != self.request_id => self.unanswered = self.unanswered.saturating_sub(1),
+                None if self.unanswered > 0 => self.unanswered -= 1,
+                _ => return Ok(move_to_play),
+            }
+        }
+    }
+
+    // A client that is gone forfeits on its next move, so failed writes are ignored here
+    fn new_game(&mut self, seat: Player, config: &GameConfig) {
+        self.send(&Request::NewGame { player: seat, opponent: self.opponent.clone(), config: *config }).ok();
+    }
+
+    fn observe(&mut self, player: Player, move_played: &Move, state: &GameState) {
+        self.send(&Request::Update { player, move_played: *move_played, state: BoardState::new(state) }).ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::thread;
+    use std::time::Duration;
+
+    use crate::bots::{Bot, BotError, MoveContext};
+    use crate::game_state::{GameStateDuringInitialPlacement, Move};
+    use crate::protocol::{MoveRequest, Request};
+
+    use super::RemoteBot;
+
+    // The client misses the deadline of the first request and answers it anyway before the second one
+    fn late_answer_is_dropped(echo_id: bool) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let client = thread::spawn(move || {
+            let mut stream = TcpStream::connect(address).unwrap();
+            writeln!(stream, r#"{{"type":"join","name":"slow"}}"#).unwrap();
+            let mut requests = BufReader::new(stream.try_clone().unwrap()).lines()
+                .filter_map(|line| match serde_json::from_str(&line.unwrap()) {
+                    Ok(Request::Move(request)) => Some(request),
+                    _ => None,
+                });
+            let mut answer = |request: MoveRequest, move_to_play: Move| {
+                let mut answer = serde_json::to_value(move_to_play).unwrap();
+                if echo_id {
+                    answer["id"] = request.id.unwrap().into();
+                }
+                writeln!(stream, "{}", answer).unwrap();
+            };
+
+            let first = requests.next().unwrap();
+            thread::sleep(Duration::from_millis(300));
+            answer(first, Move::Pass);
+            let second = requests.next().unwrap();
+            let legal = second.legal_moves[0];
+            answer(second, legal);
+        });
+
+        let mut bot = RemoteBot::accept(listener.accept().unwrap().0, Duration::from_millis(100)).unwrap();
+        let state = GameStateDuringInitialPlacement::new().place_random().start();
+        assert!(matches!(bot.try_make_move(state, &MoveContext::default()), Err(BotError::Timeout(_))));
+        bot.timeout = Duration::from_secs(5);
+        assert_eq!(bot.try_make_move(state, &MoveContext::default()).unwrap(), state.legal_moves()[0]);
+        client.join().unwrap();
+    }
+
+    #[test]
+    fn late_answer_with_id() {
+        late_answer_is_dropped(true);
+    }
+
+    #[test]
+    fn late_answer_without_id() {
+        late_answer_is_dropped(false);
+    }
+}
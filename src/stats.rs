@@ -0,0 +1,203 @@
+// z-score of the two-sided 95% confidence interval
+pub const Z_95: f64 = 1.959964;
+
+pub fn mean(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+pub fn median(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let mid = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        (sorted[mid - 1] + sorted[mid]) / 2.0
+    } else {
+        sorted[mid]
+    }
+}
+
+// Wilson score interval, which behaves well for small samples and rates close to 0 or 1
+pub fn wilson_interval(successes: f64, trials: f64, z: f64) -> (f64, f64) {
+    if trials <= 0.0 {
+        return (0.0, 1.0);
+    }
+    let p = successes / trials;
+    let z2 = z * z;
+    let denominator = 1.0 + z2 / trials;
+    let center = (p + z2 / (2.0 * trials)) / denominator;
+    let margin = z * (p * (1.0 - p) / trials + z2 / (4.0 * trials * trials)).sqrt() / denominator;
+    ((center - margin).max(0.0), (center + margin).min(1.0))
+}
+
+// Abramowitz and Stegun 7.1.26, accurate to about 1e-7
+pub fn erf(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + 0.3275911 * x.abs());
+    let polynomial = t * (0.254829592 + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+    let result = 1.0 - polynomial * (-x * x).exp();
+    if x < 0.0 { -result } else { result }
+}
+
+pub fn elo_from_score(score: f64) -> f64 {
+    let score = score.clamp(1e-6, 1.0 - 1e-6);
+    -400.0 * (1.0 / score - 1.0).log10()
+}
+
+pub fn score_from_elo(elo: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf(-elo / 400.0))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EloEstimate {
+    pub elo: f64,
+    pub low: f64,
+    pub high: f64,
+}
+
+// Mean and variance of the per game score (win 1, draw 0.5, loss 0)
+fn score_mean_and_variance(wins: u32, draws: u32, losses: u32) -> (f64, f64) {
+    let games = (wins + draws + losses) as f64;
+    let mean = (wins as f64 + draws as f64 / 2.0) / games;
+    let variance = (wins as f64 * (1.0 - mean).powi(2) + draws as f64 * (0.5 - mean).powi(2) + losses as f64 * mean.powi(2)) / games;
+    (mean, variance)
+}
+
+pub fn elo_estimate(wins: u32, draws: u32, losses: u32, z: f64) -> EloEstimate {
+    let games = wins + draws + losses;
+    if games == 0 {
+        return EloEstimate { elo: 0.0, low: f64::NEG_INFINITY, high: f64::INFINITY };
+    }
+    let (mean, variance) = score_mean_and_variance(wins, draws, losses);
+    let margin = z * (variance / games as f64).sqrt();
+    EloEstimate { elo: elo_from_score(mean), low: elo_from_score(mean - margin), high: elo_from_score(mean + margin) }
+}
+
+// Probability that the first side is stronger, draws carry no information
+pub fn likelihood_of_superiority(wins: u32, losses: u32) -> f64 {
+    if wins + losses == 0 {
+        return 0.5;
+    }
+    0.5 * (1.0 + erf((wins as f64 - losses as f64) / (2.0 * (wins + losses) as f64).sqrt()))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SprtStatus {
+    Continue,
+    AcceptH0,
+    AcceptH1,
+}
+
+// Sequential probability ratio test of H0: elo = elo0 against H1: elo = elo1
+#[derive(Debug, Clone, Copy)]
+pub struct Sprt {
+    pub elo0: f64,
+    pub elo1: f64,
+    pub alpha: f64,
+    pub beta: f64,
+}
+
+impl Sprt {
+    pub fn new(elo0: f64, elo1: f64) -> Self {
+        Self { elo0, elo1, alpha: 0.05, beta: 0.05 }
+    }
+
+    pub fn bounds(&self) -> (f64, f64) {
+        ((self.beta / (1.0 - self.alpha)).ln(), ((1.0 - self.beta) / self.alpha).ln())
+    }
+
+    // Generalized SPRT with a normal approximation of the score distribution
+    pub fn llr(&self, wins: u32, draws: u32, losses: u32) -> f64 {
+        let games = wins + draws + losses;
+        if games == 0 {
+            return 0.0;
+        }
+        let (mean, variance) = score_mean_and_variance(wins, draws, losses);
+        // Floored so that a one-sided score, like only wins, still decides the test
+        let variance = variance.max(0.25 / (games as f64 + 1.0));
+        let score0 = score_from_elo(self.elo0);
+        let score1 = score_from_elo(self.elo1);
+        games as f64 * (score1 - score0) * (2.0 * mean - score0 - score1) / (2.0 * variance)
+    }
+
+    pub fn status(&self, wins: u32, draws: u32, losses: u32) -> SprtStatus {
+        let llr = self.llr(wins, draws, losses);
+        let (lower, upper) = self.bounds();
+        if llr >= upper {
+            SprtStatus::AcceptH1
+        } else if llr <= lower {
+            SprtStatus::AcceptH0
+        } else {
+            SprtStatus::Continue
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{elo_estimate, elo_from_score, erf, likelihood_of_superiority, mean, median, score_from_elo, wilson_interval, Sprt, SprtStatus, Z_95};
+
+    #[test]
+    fn averages() {
+        assert_eq!(mean(&[]), 0.0);
+        assert_eq!(mean(&[1.0, 2.0, 6.0]), 3.0);
+        assert_eq!(median(&[5.0, 1.0, 3.0]), 3.0);
+        assert_eq!(median(&[4.0, 1.0, 3.0, 2.0]), 2.5);
+    }
+
+    #[test]
+    fn wilson_contains_rate() {
+        let (low, high) = wilson_interval(55.0, 100.0, Z_95);
+        assert!(low < 0.55 && 0.55 < high);
+        assert!((low - 0.452).abs() < 0.001);
+        assert!((high - 0.644).abs() < 0.001);
+
+        let (low, high) = wilson_interval(0.0, 10.0, Z_95);
+        assert_eq!(low, 0.0);
+        assert!(high > 0.0 && high < 0.5);
+    }
+
+    #[test]
+    fn erf_values() {
+        assert!(erf(0.0).abs() < 1e-6);
+        assert!((erf(1.0) - 0.842701).abs() < 1e-6);
+        assert!((erf(-1.0) + 0.842701).abs() < 1e-6);
+    }
+
+    #[test]
+    fn elo_conversion() {
+        assert!(elo_from_score(0.5).abs() < 1e-9);
+        assert!((elo_from_score(0.75) - 190.85).abs() < 0.01);
+        assert!((score_from_elo(elo_from_score(0.3)) - 0.3).abs() < 1e-9);
+    }
+
+    #[test]
+    fn elo_interval_contains_estimate() {
+        let estimate = elo_estimate(55, 0, 45, Z_95);
+        assert!(estimate.low < estimate.elo && estimate.elo < estimate.high);
+        assert!(estimate.low < 0.0 && estimate.high > 0.0);
+        assert!((estimate.elo - 34.86).abs() < 0.01);
+    }
+
+    #[test]
+    fn los() {
+        assert_eq!(likelihood_of_superiority(0, 0), 0.5);
+        assert!((likelihood_of_superiority(55, 45) - 0.8413).abs() < 0.001);
+        assert!(likelihood_of_superiority(10, 90) < 0.01);
+    }
+
+    #[test]
+    fn sprt_decides() {
+        let sprt = Sprt::new(0.0, 50.0);
+        assert_eq!(sprt.status(5, 0, 5), SprtStatus::Continue);
+        assert_eq!(sprt.status(300, 0, 100), SprtStatus::AcceptH1);
+        assert_eq!(sprt.status(100, 0, 300), SprtStatus::AcceptH0);
+        assert_eq!(sprt.status(1, 0, 0), SprtStatus::Continue);
+        assert_eq!(sprt.status(300, 0, 0), SprtStatus::AcceptH1);
+        assert_eq!(sprt.status(0, 0, 300), SprtStatus::AcceptH0);
+    }
+}
@@ -1,20 +1,23 @@
+use std::str::FromStr;
+
 use num_enum::TryFromPrimitive;
+use serde::{Deserialize, Serialize};
+use strum::{EnumCount as _, IntoEnumIterator};
 use strum_macros::{EnumCount, EnumIter};
 
 #[repr(u8)]
-#[derive(PartialEq, Eq, Clone, Copy, Debug, EnumCount, TryFromPrimitive)]
-#[cfg_attr(test, derive(EnumIter))]
+#[derive(PartialEq, Eq, Clone, Copy, Debug, EnumCount, EnumIter, TryFromPrimitive, Serialize, Deserialize)]
 pub enum Territory {
-    Alaska = 00,
-    NorthwestTerritory = 01,
-    Greenland = 02,
-    Alberta = 03,
-    Ontario = 04,
-    Quebec = 05,
-    WesternUnitedStates = 06,
-    EasternUnitedStates = 07,
-    CentralAmerica = 08,
-    Venezuela = 09,
+    Alaska = 0,
+    NorthwestTerritory = 1,
+    Greenland = 2,
+    Alberta = 3,
+    Ontario = 4,
+    Quebec = 5,
+    WesternUnitedStates = 6,
+    EasternUnitedStates = 7,
+    CentralAmerica = 8,
+    Venezuela = 9,
     Peru = 10,
     Brazil = 11,
     Argentina = 12,
@@ -49,7 +52,7 @@ pub enum Territory {
     Japan = 41,
 }
 
-#[derive(EnumIter, PartialEq, Eq, Clone, Copy)]
+#[derive(EnumIter, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug, Serialize, Deserialize)]
 pub enum Continent {
     NorthAmerica,
     SouthAmerica,
@@ -184,6 +187,54 @@ pub const NEIGHBORS: &[(Territory, Territory)] = &[
     (Territory::WesternAustralia, Territory::EasternAustralia),
 ];
 
+// Centre of every territory on map.png, which is 800x533 pixels
+pub const MAP_COORDS: &[(f64, f64); Territory::COUNT] = &[
+    (45.0, 80.0), // Alaska
+    (120.0, 74.0), // Northwest Territory
+    (270.0, 55.0), // Greenland
+    (110.0, 125.0), // Alberta
+    (168.0, 128.0), // Ontario
+    (220.0, 130.0), // Quebec
+    (115.0, 180.0), // Western United States
+    (170.0, 200.0), // Eastern United States
+    (125.0, 250.0), // Central America
+    (175.0, 300.0), // Venezuela
+    (190.0, 370.0), // Peru
+    (240.0, 360.0), // Brazil
+    (200.0, 440.0), // Argentina
+
+    (335.0, 100.0), // Iceland
+    (400.0, 100.0), // Scandinavia
+    (470.0, 140.0), // Ukraine
+    (310.0, 160.0), // Great Britain
+    (390.0, 180.0), // Northern Europe
+    (335.0, 240.0), // Western Europe
+    (397.0, 235.0), // Southern Europe
+    (370.0, 340.0), // North Africa
+    (425.0, 305.0), // Egypt
+    (470.0, 380.0), // East Africa
+    (425.0, 405.0), // Congo
+    (430.0, 465.0), // South Africa
+    (505.0, 475.0), // Madagascar
+
+    (550.0, 135.0), // Ural
+    (590.0, 80.0), // Siberia
+    (655.0, 60.0), // Yakutsk
+    (720.0, 65.0), // Kamchatka
+    (640.0, 130.0), // Irkutsk
+    (650.0, 185.0), // Mongolia
+    (625.0, 235.0), // China
+    (540.0, 195.0), // Afghanistan
+    (490.0, 270.0), // Middle East
+    (580.0, 280.0), // India
+    (650.0, 310.0), // Siam
+    (660.0, 400.0), // Indonesia
+    (720.0, 375.0), // New Guinea
+    (695.0, 475.0), // Western Australia
+    (755.0, 465.0), // Eastern Australia
+    (740.0, 175.0), // Japan
+];
+
 impl Territory {
     pub fn continent(self) -> Continent {
         match self {
@@ -298,6 +349,38 @@ impl std::fmt::Display for Territory {
     }
 }
 
+fn normalize_name(name: &str) -> String {
+    name.chars().filter(|c| c.is_alphanumeric()).flat_map(|c| c.to_lowercase()).collect()
+}
+
+// Case and whitespace are ignored, and any unambiguous prefix ("kam") or the initials ("wus") of a name are accepted
+impl FromStr for Territory {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let query = normalize_name(s);
+        if query.is_empty() {
+            return Err("Expected a territory".to_string());
+        }
+
+        if let Some(territory) = Territory::iter().find(|t| normalize_name(&t.to_string()) == query) {
+            return Ok(territory);
+        }
+
+        let candidates = Territory::iter().filter(|t| {
+            let name = t.to_string();
+            let initials = name.split_whitespace().filter_map(|word| word.chars().next()).flat_map(|c| c.to_lowercase()).collect::<String>();
+            normalize_name(&name).starts_with(&query) || initials == query
+        }).collect::<Vec<_>>();
+
+        match candidates.as_slice() {
+            [territory] => Ok(*territory),
+            [] => Err(format!("Unknown territory '{}'", s)),
+            _ => Err(format!("'{}' could be {}", s, candidates.iter().map(|t| t.to_string()).collect::<Vec<_>>().join(", "))),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use strum::IntoEnumIterator;
@@ -321,6 +404,21 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_names() {
+        for territory in Territory::iter() {
+            assert_eq!(territory.to_string().parse::<Territory>(), Ok(territory));
+            assert_eq!(format!("{:?}", territory).parse::<Territory>(), Ok(territory));
+        }
+        assert_eq!("kam".parse::<Territory>(), Ok(Territory::Kamchatka));
+        assert_eq!("ALASKA".parse::<Territory>(), Ok(Territory::Alaska));
+        assert_eq!("wus".parse::<Territory>(), Ok(Territory::WesternUnitedStates));
+        assert_eq!("north africa".parse::<Territory>(), Ok(Territory::NorthAfrica));
+        assert!("north".parse::<Territory>().is_err());
+        assert!("atlantis".parse::<Territory>().is_err());
+        assert!("".parse::<Territory>().is_err());
+    }
+
     #[test]
     fn check_neighbors() {
         // Check if there are any connections which are in both directions
@@ -0,0 +1,316 @@
+use std::fmt;
+
+use crate::arena::{self, ArenaOptions};
+use crate::bots::Bot;
+use crate::game::GameResult;
+use crate::game_state::GameState;
+use crate::player::Player;
+
+pub type BotFactory = Box<dyn Fn() -> Box<dyn Bot> + Send + Sync>;
+
+struct Entrant {
+    name: String,
+    factory: BotFactory,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pairing {
+    RoundRobin,
+    Swiss { rounds: usize },
+}
+
+pub struct Tournament {
+    entrants: Vec<Entrant>,
+    games_per_pairing: u32,
+    pairing: Pairing,
+    options: ArenaOptions,
+}
+
+impl Tournament {
+    pub fn new(pairing: Pairing, games_per_pairing: u32) -> Self {
+        Self { entrants: vec![], games_per_pairing, pairing, options: ArenaOptions::default() }
+    }
+
+    pub fn arena_options(mut self, options: ArenaOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn add_bot(mut self, name: &str, factory: BotFactory) -> Self {
+        self.entrants.push(Entrant { name: name.to_string(), factory });
+        self
+    }
+
+    pub fn play<Evaluator>(&self, evaluate_result: Evaluator) -> Result<TournamentResult, &'static str>
+    where
+    Evaluator: Fn(GameState) -> GameResult + Clone + Send + Sync + 'static {
+        if self.entrants.len() < 2 {
+            return Err("A tournament needs at least two bots");
+        }
+
+        let mut result = TournamentResult::new(self.entrants.iter().map(|e| e.name.clone()).collect(), self.games_per_pairing);
+        match self.pairing {
+            Pairing::RoundRobin => {
+                for idx_a in 0..(self.entrants.len() - 1) {
+                    for idx_b in (idx_a + 1)..self.entrants.len() {
+                        self.play_pairing(idx_a, idx_b, evaluate_result.clone(), &mut result)?;
+                    }
+                }
+            }
+            Pairing::Swiss { rounds } => {
+                for _ in 0..rounds {
+                    let (pairs, bye) = swiss_pairings(&result);
+                    for (idx_a, idx_b) in pairs {
+                        self.play_pairing(idx_a, idx_b, evaluate_result.clone(), &mut result)?;
+                    }
+                    if let Some(idx) = bye {
+                        result.byes[idx] += 1;
+                    }
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    // Both bots get seat A for half of the games, so the first move advantage cancels out
+    fn play_pairing<Evaluator>(&self, idx_a: usize, idx_b: usize, evaluate_result: Evaluator, result: &mut TournamentResult) -> Result<(), &'static str>
+    where
+    Evaluator: Fn(GameState) -> GameResult + Clone + Send + Sync + 'static {
+        let first_half = self.games_per_pairing / 2;
+        let second_half = self.games_per_pairing - first_half;
+
+        let factory_a = &self.entrants[idx_a].factory;
+        let factory_b = &self.entrants[idx_b].factory;
+
+        let games = arena::play_games_with_options(second_half, evaluate_result.clone(), factory_a, factory_b, &self.options)?;
+        result.record(idx_a, idx_b, games.wins(Player::A), games.draws(), games.wins(Player::B));
+
+        let games = arena::play_games_with_options(first_half, evaluate_result, factory_b, factory_a, &self.options)?;
+        result.record(idx_b, idx_a, games.wins(Player::A), games.draws(), games.wins(Player::B));
+
+        Ok(())
+    }
+}
+
+// Pairs neighbours in the current standings, avoiding rematches where possible. The lowest ranked bot
+// without a bye sits out if the number of bots is odd.
+fn swiss_pairings(result: &TournamentResult) -> (Vec<(usize, usize)>, Option<usize>) {
+    let mut order = (0..result.names.len()).collect::<Vec<_>>();
+    order.sort_by(|&a, &b| result.points(b).partial_cmp(&result.points(a)).unwrap());
+
+    let mut bye = None;
+    if order.len() % 2 == 1 {
+        let position = order.iter().rposition(|&idx| result.byes[idx] == 0).unwrap_or(order.len() - 1);
+        bye = Some(order.remove(position));
+    }
+
+    let mut pairs = vec![];
+    while !order.is_empty() {
+        let idx_a = order.remove(0);
+        let position = order.iter().position(|&idx_b| result.games_between(idx_a, idx_b) == 0).unwrap_or(0);
+        pairs.push((idx_a, order.remove(position)));
+    }
+    (pairs, bye)
+}
+
+// Bradley-Terry maximum likelihood fit by minorization-maximization, draws count as half a win.
+// Every pairing that was played gets one virtual draw, so bots that won or lost every game still get a finite rating.
+pub fn estimate_elo(scores: &[Vec<f64>], games: &[Vec<u32>]) -> Vec<f64> {
+    const ITERATIONS: usize = 1000;
+
+    let count = scores.len();
+    let mut gamma = vec![1.0; count];
+    for _ in 0..ITERATIONS {
+        let mut next = gamma.clone();
+        for i in 0..count {
+            let mut wins = 0.0;
+            let mut denominator = 0.0;
+            for j in 0..count {
+                let n = (games[i][j] + games[j][i]) as f64;
+                if i == j || n == 0.0 {
+                    continue;
+                }
+                wins += scores[i][j] + 0.5;
+                denominator += (n + 1.0) / (gamma[i] + gamma[j]);
+            }
+            if denominator > 0.0 {
+                next[i] = wins / denominator;
+            }
+        }
+        let geometric_mean = (next.iter().map(|g: &f64| g.ln()).sum::<f64>() / count as f64).exp();
+        gamma = next.iter().map(|g| g / geometric_mean).collect();
+    }
+    gamma.iter().map(|g| 400.0 * g.log10()).collect()
+}
+
+#[derive(Debug, Clone)]
+pub struct Standing {
+    pub name: String,
+    pub points: f64,
+    pub games: u32,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+    pub elo: f64,
+}
+
+pub struct TournamentResult {
+    names: Vec<String>,
+    games_per_pairing: u32,
+    // Indexed by [bot][opponent], from the view of the first bot
+    wins: Vec<Vec<u32>>,
+    draws: Vec<Vec<u32>>,
+    byes: Vec<u32>,
+}
+
+impl TournamentResult {
+    fn new(names: Vec<String>, games_per_pairing: u32) -> Self {
+        let count = names.len();
+        Self { names, games_per_pairing, wins: vec![vec![0; count]; count], draws: vec![vec![0; count]; count], byes: vec![0; count] }
+    }
+
+    fn record(&mut self, idx_a: usize, idx_b: usize, wins_a: u32, draws: u32, wins_b: u32) {
+        self.wins[idx_a][idx_b] += wins_a;
+        self.wins[idx_b][idx_a] += wins_b;
+        self.draws[idx_a][idx_b] += draws;
+        self.draws[idx_b][idx_a] += draws;
+    }
+
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    pub fn games_between(&self, idx_a: usize, idx_b: usize) -> u32 {
+        self.wins[idx_a][idx_b] + self.wins[idx_b][idx_a] + self.draws[idx_a][idx_b]
+    }
+
+    // Points scored by the first bot against the second, a win is worth one point and a draw half
+    pub fn score(&self, idx_a: usize, idx_b: usize) -> f64 {
+        self.wins[idx_a][idx_b] as f64 + self.draws[idx_a][idx_b] as f64 / 2.0
+    }
+
+    pub fn points(&self, idx: usize) -> f64 {
+        (0..self.names.len()).map(|opponent| self.score(idx, opponent)).sum::<f64>() + (self.byes[idx] * self.games_per_pairing) as f64
+    }
+
+    pub fn elo(&self) -> Vec<f64> {
+        let count = self.names.len();
+        let scores = (0..count).map(|i| (0..count).map(|j| self.score(i, j)).collect()).collect::<Vec<Vec<f64>>>();
+        let games = (0..count).map(|i| (0..count).map(|j| if i < j { self.games_between(i, j) } else { 0 }).collect()).collect::<Vec<Vec<u32>>>();
+        estimate_elo(&scores, &games)
+    }
+
+    pub fn standings(&self) -> Vec<Standing> {
+        let elo = self.elo();
+        let mut standings = (0..self.names.len()).map(|idx| {
+            let wins = self.wins[idx].iter().sum();
+            let draws = self.draws[idx].iter().sum();
+            let losses = (0..self.names.len()).map(|opponent| self.wins[opponent][idx]).sum();
+            Standing { name: self.names[idx].clone(), points: self.points(idx), games: wins + draws + losses, wins, draws, losses, elo: elo[idx] }
+        }).collect::<Vec<_>>();
+        standings.sort_by(|a, b| b.points.partial_cmp(&a.points).unwrap().then(b.elo.partial_cmp(&a.elo).unwrap()));
+        standings
+    }
+}
+
+impl fmt::Display for TournamentResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self.names.iter().map(|name| name.len()).max().unwrap_or(0).max(6);
+
+        write!(f, "{:width$}", "", width = width)?;
+        for name in &self.names {
+            write!(f, " {:>width$}", name, width = width)?;
+        }
+        writeln!(f)?;
+        for (idx, name) in self.names.iter().enumerate() {
+            write!(f, "{:width$}", name, width = width)?;
+            for opponent in 0..self.names.len() {
+                if idx == opponent || self.games_between(idx, opponent) == 0 {
+                    write!(f, " {:>width$}", "-", width = width)?;
+                } else {
+                    let cell = format!("{}/{}", self.score(idx, opponent), self.games_between(idx, opponent));
+                    write!(f, " {:>width$}", cell, width = width)?;
+                }
+            }
+            writeln!(f)?;
+        }
+
+        writeln!(f)?;
+        writeln!(f, "{:>3} {:width$} {:>7} {:>5} {:>5} {:>5} {:>5} {:>7}", "#", "Bot", "Points", "Games", "W", "D", "L", "Elo", width = width)?;
+        for (rank, standing) in self.standings().iter().enumerate() {
+            writeln!(f, "{:>3} {:width$} {:>7} {:>5} {:>5} {:>5} {:>5} {:>7.0}", rank + 1, standing.name, standing.points, standing.games, standing.wins, standing.draws, standing.losses, standing.elo, width = width)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{estimate_elo, swiss_pairings, TournamentResult};
+
+    fn names(count: usize) -> Vec<String> {
+        (0..count).map(|idx| format!("bot{}", idx)).collect()
+    }
+
+    #[test]
+    fn elo_orders_by_strength() {
+        let scores = vec![vec![0.0, 15.0, 18.0], vec![5.0, 0.0, 12.0], vec![2.0, 8.0, 0.0]];
+        let games = vec![vec![0, 20, 20], vec![0, 0, 20], vec![0, 0, 0]];
+        let elo = estimate_elo(&scores, &games);
+        assert!(elo[0] > elo[1] && elo[1] > elo[2]);
+        assert!(elo.iter().sum::<f64>().abs() < 1e-6);
+    }
+
+    #[test]
+    fn elo_of_even_match_is_zero() {
+        let elo = estimate_elo(&[vec![0.0, 10.0], vec![10.0, 0.0]], &[vec![0, 20], vec![0, 0]]);
+        assert!(elo[0].abs() < 1e-6 && elo[1].abs() < 1e-6);
+    }
+
+    #[test]
+    fn elo_is_finite_for_perfect_scores() {
+        let elo = estimate_elo(&[vec![0.0, 10.0], vec![0.0, 0.0]], &[vec![0, 10], vec![0, 0]]);
+        assert!(elo[0].is_finite() && elo[0] > 0.0);
+    }
+
+    #[test]
+    fn standings_and_points() {
+        let mut result = TournamentResult::new(names(3), 4);
+        result.record(0, 1, 3, 1, 0);
+        result.record(1, 2, 2, 0, 2);
+        result.record(0, 2, 4, 0, 0);
+
+        assert_eq!(result.points(0), 7.5);
+        assert_eq!(result.points(1), 2.5);
+        assert_eq!(result.games_between(1, 0), 4);
+        let standings = result.standings();
+        assert_eq!(standings[0].name, "bot0");
+        assert_eq!((standings[0].wins, standings[0].draws, standings[0].losses), (7, 1, 0));
+    }
+
+    #[test]
+    fn swiss_avoids_rematches() {
+        let mut result = TournamentResult::new(names(4), 2);
+        let (pairs, bye) = swiss_pairings(&result);
+        assert_eq!(bye, None);
+        assert_eq!(pairs.len(), 2);
+
+        result.record(0, 1, 2, 0, 0);
+        result.record(2, 3, 2, 0, 0);
+        let (pairs, _) = swiss_pairings(&result);
+        for (a, b) in pairs {
+            assert_eq!(result.games_between(a, b), 0);
+        }
+    }
+
+    #[test]
+    fn swiss_gives_bye_once() {
+        let mut result = TournamentResult::new(names(3), 2);
+        let (pairs, bye) = swiss_pairings(&result);
+        assert_eq!(pairs.len(), 1);
+        let bye = bye.unwrap();
+        result.byes[bye] += 1;
+        let (_, next_bye) = swiss_pairings(&result);
+        assert_ne!(next_bye, Some(bye));
+    }
+}